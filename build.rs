@@ -0,0 +1,87 @@
+// Turns every `src/dayNN/examples.toml` into named test cases, which
+// `tests/examples.rs` includes
+use std::{env, fmt::Write, fs, path::Path};
+
+fn expected(value: &toml::Value, manifest: &Path) -> String {
+    match value {
+        toml::Value::Integer(n) => n.to_string(),
+        toml::Value::String(s) => s.clone(),
+        other => panic!(
+            "{}: answers must be integers or strings, not {other}",
+            manifest.display()
+        ),
+    }
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+
+    let mut days: Vec<_> = fs::read_dir("src")
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.join("examples.toml").is_file())
+        .collect();
+    days.sort();
+
+    let mut tests = String::new();
+    for dir in days {
+        let name = dir.file_name().unwrap().to_string_lossy().to_string();
+        let Some(day) = name
+            .strip_prefix("day")
+            .and_then(|day| day.parse::<u8>().ok())
+        else {
+            continue;
+        };
+
+        let manifest = dir.join("examples.toml");
+        let content = fs::read_to_string(&manifest).unwrap();
+        let table: toml::Table =
+            toml::from_str(&content).unwrap_or_else(|e| panic!("{}: {e}", manifest.display()));
+
+        let examples = match table.get("example") {
+            Some(toml::Value::Array(examples)) => examples.clone(),
+            None => Vec::new(),
+            Some(_) => panic!("{}: `example` must be a list", manifest.display()),
+        };
+
+        for example in examples {
+            let toml::Value::Table(example) = example else {
+                panic!("{}: each example must be a table", manifest.display());
+            };
+            for key in example.keys() {
+                if !["file", "part_one", "part_two"].contains(&key.as_str()) {
+                    panic!("{}: unknown key `{key}`", manifest.display());
+                }
+            }
+
+            let Some(toml::Value::String(file)) = example.get("file") else {
+                panic!("{}: each example needs a `file`", manifest.display());
+            };
+            let path = fs::canonicalize(dir.join(file))
+                .unwrap_or_else(|e| panic!("{}: {file}: {e}", manifest.display()));
+            let stem: String = Path::new(file)
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+
+            for (part, key) in [(1, "part_one"), (2, "part_two")] {
+                let Some(value) = example.get(key) else {
+                    continue;
+                };
+                writeln!(
+                    tests,
+                    "#[test]\nfn {name}_{stem}_{key}() {{\n    check({day}, {part}, include_str!({path:?}), {:?});\n}}\n",
+                    expected(value, &manifest)
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("examples.rs");
+    fs::write(out, tests).unwrap();
+}
@@ -0,0 +1,297 @@
+use crate::{
+    cli::{AnimateArgs, Selection},
+    error::AocError,
+    grid::Pos,
+    render::{self, Cell, Render, Viewport},
+    runner,
+};
+use std::{cell::RefCell, error::Error, fmt::Write as _, fs, io, path::Path};
+
+/// Which steps of a simulation become frames.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Options {
+    /// Keep one step in this many.
+    pub every: usize,
+    /// Only keep this part of each state.
+    pub crop: Option<Viewport>,
+    /// Stop keeping frames after this many.
+    pub max_frames: usize,
+}
+
+/// A snapshot of a simulation's state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
+
+impl Frame {
+    pub fn capture(state: &dyn Render, crop: Option<Viewport>) -> Self {
+        let rows = Viewport::rows(crop, state);
+        Frame {
+            width: rows.first().map_or(0, Vec::len),
+            height: rows.len(),
+            cells: rows
+                .into_iter()
+                .flatten()
+                .map(|pos| state.cell(pos))
+                .collect(),
+        }
+    }
+}
+
+impl Render for Frame {
+    fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn cell(&self, (x, y): Pos) -> Cell {
+        self.cells[y * self.width + x]
+    }
+}
+
+struct Recorder {
+    options: Options,
+    steps: usize,
+    frames: Vec<Frame>,
+}
+
+thread_local! {
+    static RECORDER: RefCell<Option<Recorder>> = const { RefCell::new(None) };
+}
+
+// Puts the previous recorder back even if the part panics
+struct Restore(Option<Recorder>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        RECORDER.set(self.0.take());
+    }
+}
+
+/// Runs `f`, keeping frames of the states it passes to `capture` on this
+/// thread.
+pub fn record<T>(options: Options, f: impl FnOnce() -> T) -> (T, Vec<Frame>) {
+    let recorder = Recorder {
+        options,
+        steps: 0,
+        frames: Vec::new(),
+    };
+    let mut restore = Restore(RECORDER.replace(Some(recorder)));
+    let result = f();
+    let frames = RECORDER
+        .replace(restore.0.take())
+        .map(|recorder| recorder.frames)
+        .unwrap_or_default();
+    (result, frames)
+}
+
+/// Offers one step of a simulation to the recorder. `state` is only called
+/// for steps that become frames, so outside `record` this costs next to
+/// nothing.
+pub fn capture<R: Render>(state: impl FnOnce() -> R) {
+    RECORDER.with_borrow_mut(|recorder| {
+        let Some(recorder) = recorder else {
+            return;
+        };
+        let step = recorder.steps;
+        recorder.steps += 1;
+        if step % recorder.options.every.max(1) == 0
+            && recorder.frames.len() < recorder.options.max_frames
+        {
+            let frame = Frame::capture(&state(), recorder.options.crop);
+            recorder.frames.push(frame);
+        }
+    })
+}
+
+/// Writes `frames` in the format `path` names: a `.gif`, an animated `.svg`,
+/// or otherwise a directory of numbered PNGs.
+pub fn export(frames: &[Frame], path: &Path, fps: u32, scale: usize) -> Result<(), AocError> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("gif") => fs::write(path, gif(frames, fps, scale)?)?,
+        Some("svg") => fs::write(path, svg(frames, fps, scale))?,
+        _ => {
+            fs::create_dir_all(path)?;
+            for (i, frame) in frames.iter().enumerate() {
+                let png = render::png(frame, None, scale)?;
+                fs::write(path.join(format!("frame-{:05}.png", i + 1)), png)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// An endlessly looping GIF.
+pub fn gif(frames: &[Frame], fps: u32, scale: usize) -> Result<Vec<u8>, AocError> {
+    let Some(first) = frames.first() else {
+        return Err(AocError::invalid("no frames to animate"));
+    };
+    let too_large = || AocError::invalid("frames are too large for a GIF");
+    let width = u16::try_from(first.width * scale).map_err(|_| too_large())?;
+    let height = u16::try_from(first.height * scale).map_err(|_| too_large())?;
+    // GIF delays are in hundredths of a second
+    let delay = (100 / fps.max(1)).max(1) as u16;
+
+    let mut gif = Vec::new();
+    {
+        let mut encoder =
+            gif::Encoder::new(&mut gif, width, height, &[]).map_err(io::Error::other)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(io::Error::other)?;
+        for frame in frames {
+            let (pixels, _, _) = render::pixels(frame, None, scale);
+            let mut frame = gif::Frame::from_rgb_speed(width, height, &pixels, 10);
+            frame.delay = delay;
+            encoder.write_frame(&frame).map_err(io::Error::other)?;
+        }
+    }
+    Ok(gif)
+}
+
+/// An SVG that shows each frame in turn, looping forever.
+pub fn svg(frames: &[Frame], fps: u32, scale: usize) -> String {
+    let (width, height) = frames.first().map_or((0, 0), Render::size);
+    let count = frames.len();
+    let duration = count as f64 / fps.max(1) as f64;
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" style="background-color: white">"#,
+        width * scale,
+        height * scale
+    );
+    for (i, frame) in frames.iter().enumerate() {
+        // each frame shows from i/count of the way through until (i+1)/count
+        let (start, end) = (i as f64 / count as f64, (i + 1) as f64 / count as f64);
+        let (values, key_times) = match (i, i + 1 == count) {
+            (_, true) if i == 0 => ("inline".to_string(), "0".to_string()),
+            (0, false) => ("inline;none".to_string(), format!("0;{end}")),
+            (_, true) => ("none;inline".to_string(), format!("0;{start}")),
+            _ => ("none;inline;none".to_string(), format!("0;{start};{end}")),
+        };
+        let _ = write!(
+            svg,
+            r#"<g display="none"><animate attributeName="display" values="{values}" keyTimes="{key_times}" dur="{duration}s" calcMode="discrete" repeatCount="indefinite" />{}</g>"#,
+            render::svg_layers(frame, None, scale)
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+pub fn animate(args: &AnimateArgs) -> Result<(), Box<dyn Error>> {
+    let plan = runner::plan(&Selection {
+        days: Some(args.day..=args.day),
+        all: false,
+        part: Some(args.part),
+        input: args.input.clone(),
+        timeout: None,
+    })?;
+    runner::check_inputs(&plan)?;
+    let part = &plan[0];
+    let content = runner::read_input(&part.input)?;
+
+    let options = Options {
+        every: args.every,
+        crop: args.crop,
+        max_frames: args.max_frames,
+    };
+    let (answer, frames) = record(options, || part.run(&content));
+    match answer {
+        Ok(answer) => println!("{} -> {answer}", part.label()),
+        Err(e) => eprintln!("{} failed: {e}", part.label()),
+    }
+    if frames.is_empty() {
+        return Err(format!("{} did not capture any frames", part.label()).into());
+    }
+
+    export(&frames, &args.output, args.fps, args.scale)?;
+    println!("Wrote {} frames to {}", frames.len(), args.output.display());
+    if frames.len() == args.max_frames {
+        println!("Stopped at --max-frames; raise it or --every to see the rest");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::Layer;
+
+    // A dot walking right along a row
+    struct Walk(usize);
+
+    impl Render for Walk {
+        fn size(&self) -> (usize, usize) {
+            (4, 2)
+        }
+
+        fn cell(&self, (x, y): Pos) -> Cell {
+            if (x, y) == (self.0, 0) {
+                Cell::new('@', Layer::Entity)
+            } else {
+                Cell::new('.', Layer::Background)
+            }
+        }
+    }
+
+    fn walk() -> usize {
+        for step in 0..4 {
+            capture(|| Walk(step));
+        }
+        4
+    }
+
+    #[test]
+    fn records() {
+        assert_eq!(walk(), 4);
+
+        let options = Options {
+            every: 2,
+            crop: Some(Viewport {
+                x: 1,
+                y: 0,
+                width: 5,
+                height: 1,
+            }),
+            max_frames: 10,
+        };
+        let (steps, frames) = record(options, walk);
+        assert_eq!(steps, 4);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(render::text(&frames[0], None), "...\n");
+        assert_eq!(render::text(&frames[1], None), ".@.\n");
+
+        let options = Options {
+            every: 1,
+            crop: None,
+            max_frames: 3,
+        };
+        assert_eq!(record(options, walk).1.len(), 3);
+    }
+
+    #[test]
+    fn exports() {
+        let options = Options {
+            every: 1,
+            crop: None,
+            max_frames: 10,
+        };
+        let (_, frames) = record(options, walk);
+
+        let gif = gif(&frames, 10, 2).unwrap();
+        assert!(gif.starts_with(b"GIF89a"));
+        assert!(super::gif(&[], 10, 2).is_err());
+
+        let svg = svg(&frames, 4, 2);
+        assert_eq!(svg.matches("<animate").count(), 4);
+        assert!(svg.contains(r#"values="none;inline;none" keyTimes="0;0.25;0.5" dur="1s""#));
+
+        let dir = std::env::temp_dir().join(format!("aoc-frames-{}", std::process::id()));
+        export(&frames, &dir, 10, 1).unwrap();
+        assert!(dir.join("frame-00004.png").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
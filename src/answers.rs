@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, error::Error, fs, path::Path};
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DayAnswers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part_one: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part_two: Option<String>,
+}
+
+/// Known answers for each day's real `input.txt`, keyed by `dayNN`.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Answers(BTreeMap<String, DayAnswers>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    Pass,
+    Fail,
+    Unknown,
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        if !path.exists() {
+            return Ok(Answers::default());
+        }
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content)
+            .map_err(|e| format!("could not parse {}: {e}", path.display()).into())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        let answers = self.0.get(&format!("day{day:02}"))?;
+        match part {
+            1 => answers.part_one.as_deref(),
+            _ => answers.part_two.as_deref(),
+        }
+    }
+
+    pub fn set(&mut self, day: u8, part: u8, answer: String) {
+        let answers = self.0.entry(format!("day{day:02}")).or_default();
+        match part {
+            1 => answers.part_one = Some(answer),
+            _ => answers.part_two = Some(answer),
+        }
+    }
+
+    pub fn check(&self, day: u8, part: u8, answer: &str) -> Verdict {
+        match self.get(day, part) {
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(_) => Verdict::Fail,
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut answers = Answers::default();
+        answers.set(9, 1, "6349606724455".to_string());
+        answers.set(1, 2, "31".to_string());
+
+        let content = toml::to_string(&answers).unwrap();
+        assert_eq!(
+            content,
+            "[day01]\npart_two = \"31\"\n\n[day09]\npart_one = \"6349606724455\"\n"
+        );
+        assert_eq!(toml::from_str::<Answers>(&content).unwrap(), answers);
+    }
+
+    #[test]
+    fn verdicts() {
+        let mut answers = Answers::default();
+        answers.set(1, 1, "11".to_string());
+
+        assert_eq!(answers.check(1, 1, "11"), Verdict::Pass);
+        assert_eq!(answers.check(1, 1, "12"), Verdict::Fail);
+        assert_eq!(answers.check(1, 2, "31"), Verdict::Unknown);
+    }
+}
@@ -0,0 +1,343 @@
+use crate::{
+    cli::{BenchArgs, CompareArgs},
+    runner::{self, Part},
+    time_it,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fs,
+    hint::black_box,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Raw samples from one `bench` invocation, in nanoseconds.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BenchRun {
+    pub created: u64,
+    pub results: Vec<PartSamples>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PartSamples {
+    pub day: u8,
+    pub part: u8,
+    pub io: Vec<u64>,
+    pub parse: Vec<u64>,
+    pub solve: Vec<u64>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub min: f64,
+    pub median: f64,
+    pub mean: f64,
+    pub stddev: f64,
+    pub p95: f64,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[u64]) -> Self {
+        let mut sorted: Vec<f64> = samples.iter().map(|s| *s as f64).collect();
+        sorted.sort_by(f64::total_cmp);
+
+        let n = sorted.len() as f64;
+        let mean = sorted.iter().sum::<f64>() / n;
+        let variance = if sorted.len() > 1 {
+            sorted.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+
+        Stats {
+            min: sorted[0],
+            median: percentile(&sorted, 0.5),
+            mean,
+            stddev: variance.sqrt(),
+            p95: percentile(&sorted, 0.95),
+        }
+    }
+}
+
+// Nearest-rank percentile of already sorted samples
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.saturating_sub(1)]
+}
+
+fn sample(part: &Part, samples: &mut PartSamples) -> Result<(), Box<dyn Error>> {
+    let (content, io) = time_it!(runner::read_input(&part.input));
+    let content = content?;
+    let (input, parse) = time_it!(part.solution.parse(&content));
+    let input = input?;
+    let (answer, solve) = time_it!(part.solve(input.as_ref()));
+    black_box(answer?);
+
+    samples.io.push(io.as_nanos() as u64);
+    samples.parse.push(parse.as_nanos() as u64);
+    samples.solve.push(solve.as_nanos() as u64);
+    Ok(())
+}
+
+fn measure(part: &Part, args: &BenchArgs) -> Result<PartSamples, Box<dyn Error>> {
+    let mut samples = PartSamples {
+        day: part.solution.day(),
+        part: part.part,
+        io: Vec::new(),
+        parse: Vec::new(),
+        solve: Vec::new(),
+    };
+
+    for _ in 0..args.warmup {
+        sample(part, &mut samples)?;
+    }
+    samples.io.clear();
+    samples.parse.clear();
+    samples.solve.clear();
+
+    match args.budget {
+        Some(budget) => {
+            let start = Instant::now();
+            // Always take a couple of samples so there is a spread to report
+            while samples.solve.len() < 2 || start.elapsed() < budget {
+                sample(part, &mut samples)?;
+            }
+        }
+        None => {
+            for _ in 0..args.iterations.max(1) {
+                sample(part, &mut samples)?;
+            }
+        }
+    }
+
+    Ok(samples)
+}
+
+fn ms(nanos: f64) -> f64 {
+    Duration::from_nanos(nanos as u64).as_secs_f64() * 1000.0
+}
+
+fn print_stats(stage: &str, samples: &[u64]) {
+    let stats = Stats::from_samples(samples);
+    println!(
+        "\t    {stage:<5} min {:>8.3}ms  median {:>8.3}ms  mean {:>8.3}ms  stddev {:>7.3}ms  p95 {:>8.3}ms",
+        ms(stats.min),
+        ms(stats.median),
+        ms(stats.mean),
+        ms(stats.stddev),
+        ms(stats.p95),
+    );
+}
+
+pub fn bench(args: &BenchArgs) -> Result<(), Box<dyn Error>> {
+    let plan = runner::plan(&args.selection)?;
+    runner::check_inputs(&plan)?;
+
+    let mut run = BenchRun {
+        created: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        results: Vec::new(),
+    };
+
+    for part in plan {
+        let samples = measure(&part, args)?;
+
+        println!("\t{} ({} runs)", part.label(), samples.solve.len());
+        print_stats("io", &samples.io);
+        print_stats("parse", &samples.parse);
+        print_stats("solve", &samples.solve);
+
+        run.results.push(samples);
+    }
+
+    if let Some(path) = &args.save {
+        fs::write(path, serde_json::to_string_pretty(&run)?)?;
+        println!("\n\tSaved samples to {}", path.display());
+    }
+
+    Ok(())
+}
+
+// Abramowitz and Stegun 7.1.26, accurate to about 1.5e-7
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let y = 1.0 - poly * (-x * x).exp();
+    y.copysign(x)
+}
+
+fn normal_cdf(z: f64) -> f64 {
+    0.5 * (1.0 + erf(z / std::f64::consts::SQRT_2))
+}
+
+/// One-sided Mann-Whitney U test using the normal approximation. Returns the
+/// probability of seeing `candidate` rank this high if it were no slower than
+/// `baseline`. Timing samples are rarely normal, so a rank test holds up
+/// better than comparing means.
+pub fn slower_p_value(baseline: &[u64], candidate: &[u64]) -> f64 {
+    let mut all: Vec<(u64, bool)> = baseline
+        .iter()
+        .map(|s| (*s, false))
+        .chain(candidate.iter().map(|s| (*s, true)))
+        .collect();
+    all.sort();
+
+    // Tied samples share the average of their ranks
+    let mut candidate_rank_sum = 0.0;
+    let mut i = 0;
+    while i < all.len() {
+        let mut j = i;
+        while j < all.len() && all[j].0 == all[i].0 {
+            j += 1;
+        }
+        let rank = (i + j + 1) as f64 / 2.0;
+        candidate_rank_sum += rank * all[i..j].iter().filter(|s| s.1).count() as f64;
+        i = j;
+    }
+
+    let n1 = candidate.len() as f64;
+    let n2 = baseline.len() as f64;
+    let u = candidate_rank_sum - n1 * (n1 + 1.0) / 2.0;
+    let mean = n1 * n2 / 2.0;
+    let sd = (n1 * n2 * (n1 + n2 + 1.0) / 12.0).sqrt();
+    if sd == 0.0 {
+        return 1.0;
+    }
+
+    1.0 - normal_cdf((u - mean - 0.5) / sd)
+}
+
+fn load(path: &std::path::Path) -> Result<BenchRun, Box<dyn Error>> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("could not read {}: {e}", path.display()))?;
+    let run: BenchRun = serde_json::from_str(&content)?;
+
+    // statistics need at least one sample per stage
+    for result in &run.results {
+        for (stage, samples) in [
+            ("io", &result.io),
+            ("parse", &result.parse),
+            ("solve", &result.solve),
+        ] {
+            if samples.is_empty() {
+                return Err(format!(
+                    "{}: day{:02} part {} has no {stage} samples",
+                    path.display(),
+                    result.day,
+                    result.part
+                )
+                .into());
+            }
+        }
+    }
+
+    Ok(run)
+}
+
+// Percentage change from `old` to `new`, which is undefined when `old` is zero
+pub(crate) fn percent_change(old: f64, new: f64) -> Option<f64> {
+    (old > 0.0).then(|| (new - old) / old * 100.0)
+}
+
+pub fn compare(args: &CompareArgs) -> Result<(), Box<dyn Error>> {
+    let baseline = load(&args.baseline)?;
+    let candidate = load(&args.candidate)?;
+
+    let mut slowdowns = 0;
+    for new in &candidate.results {
+        let Some(old) = baseline
+            .results
+            .iter()
+            .find(|old| old.day == new.day && old.part == new.part)
+        else {
+            continue;
+        };
+
+        for (stage, old_samples, new_samples) in [
+            ("parse", &old.parse, &new.parse),
+            ("solve", &old.solve, &new.solve),
+        ] {
+            let old_median = Stats::from_samples(old_samples).median;
+            let new_median = Stats::from_samples(new_samples).median;
+            let change = percent_change(old_median, new_median);
+            let p = slower_p_value(old_samples, new_samples);
+
+            let verdict = match change {
+                Some(change) if p < args.alpha && change > args.threshold => {
+                    slowdowns += 1;
+                    "SLOWER"
+                }
+                Some(change)
+                    if change < -args.threshold
+                        && slower_p_value(new_samples, old_samples) < args.alpha =>
+                {
+                    "faster"
+                }
+                _ => "",
+            };
+
+            println!(
+                "\tday{:02}::part_{} {stage:<5} {:>8.3}ms -> {:>8.3}ms {:>8} p={p:.3} {verdict}",
+                new.day,
+                if new.part == 1 { "one" } else { "two" },
+                ms(old_median),
+                ms(new_median),
+                change.map_or("n/a".to_string(), |change| format!("{change:+.1}%")),
+            );
+        }
+    }
+
+    if slowdowns > 0 {
+        return Err(format!("{slowdowns} significant slowdown(s)").into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let stats = Stats::from_samples(&[5, 1, 4, 2, 3]);
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.median, 3.0);
+        assert_eq!(stats.mean, 3.0);
+        assert!((stats.stddev - 2.5_f64.sqrt()).abs() < 1e-9);
+        assert_eq!(stats.p95, 5.0);
+    }
+
+    #[test]
+    fn detects_slowdowns() {
+        let baseline = [100, 102, 98, 101, 99, 100, 103, 97, 100, 101];
+        let slower = [120, 122, 118, 121, 119, 120, 123, 117, 120, 121];
+
+        assert!(slower_p_value(&baseline, &slower) < 0.01);
+        assert!(slower_p_value(&slower, &baseline) > 0.99);
+        assert!(slower_p_value(&baseline, &baseline) > 0.4);
+    }
+
+    #[test]
+    fn changes() {
+        assert_eq!(percent_change(100.0, 120.0), Some(20.0));
+        assert_eq!(percent_change(0.0, 120.0), None);
+        assert_eq!(percent_change(0.0, 0.0), None);
+    }
+
+    #[test]
+    fn rejects_empty_runs() {
+        let path = std::env::temp_dir().join(format!("aoc-empty-run-{}.json", std::process::id()));
+        fs::write(
+            &path,
+            r#"{"created":0,"results":[{"day":7,"part":1,"io":[1],"parse":[],"solve":[1]}]}"#,
+        )
+        .unwrap();
+        let err = load(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert!(err
+            .to_string()
+            .ends_with("day07 part 1 has no parse samples"));
+    }
+}
@@ -0,0 +1,401 @@
+use crate::render::Viewport;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::{ops::RangeInclusive, path::PathBuf, time::Duration};
+
+#[derive(Debug, Parser)]
+#[command(name = "aoc-2024", about = "Advent of Code 2024 solutions")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Log a day's trace events to stderr, e.g. `day09=debug`, or `trace`
+    /// for every day
+    #[arg(long, global = true)]
+    pub trace: Vec<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run one or more days and print their answers
+    Run(RunArgs),
+    /// Repeatedly time one or more days and report timing statistics
+    Bench(BenchArgs),
+    /// Compare two saved benchmark runs and flag slowdowns
+    BenchCompare(CompareArgs),
+    /// Show how each part's timing has changed across runs
+    History(HistoryArgs),
+    /// Download puzzle inputs to `src/dayNN/input.txt`
+    Fetch(FetchArgs),
+    /// Solve one part against the real input and submit the answer
+    Submit(SubmitArgs),
+    /// Create and register a module for a new day
+    New(NewArgs),
+    /// Rebuild and re-run a day whenever its source or inputs change
+    Watch(WatchArgs),
+    /// Explore solutions interactively, with inputs and parameters set by hand
+    Repl(ReplArgs),
+    /// Record the steps of a day's simulation and export them as an animation
+    Animate(AnimateArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub selection: Selection,
+
+    /// How to print the results
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+
+    /// Known answers to check results against
+    #[arg(long, default_value = "answers.toml")]
+    pub answers: PathBuf,
+
+    /// Save answers for parts that do not have one yet
+    #[arg(long)]
+    pub record: bool,
+
+    /// Run parts concurrently on all cores
+    #[arg(long)]
+    pub parallel: bool,
+
+    /// File that timings of runs against the real inputs are appended to.
+    /// Parallel runs are left out, as their parts compete for cores
+    #[arg(long, default_value = "history.jsonl")]
+    pub history: PathBuf,
+
+    /// Don't append timings to the history, e.g. for debug builds
+    #[arg(long, conflicts_with = "history")]
+    pub no_history: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    #[command(flatten)]
+    pub selection: Selection,
+
+    /// Untimed runs of each part before measuring
+    #[arg(long, default_value_t = 3)]
+    pub warmup: u32,
+
+    /// Timed runs of each part
+    #[arg(long, short = 'n', default_value_t = 10, conflicts_with = "budget")]
+    pub iterations: u32,
+
+    /// Keep repeating each part until this much time has been spent, e.g. `500ms` or `2s`
+    #[arg(long, value_parser = parse_duration)]
+    pub budget: Option<Duration>,
+
+    /// Save the raw samples as JSON so the run can be compared later
+    #[arg(long)]
+    pub save: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct CompareArgs {
+    /// Benchmark run saved with `bench --save` to compare against
+    pub baseline: PathBuf,
+
+    /// Newer benchmark run
+    pub candidate: PathBuf,
+
+    /// Significance level for the rank-sum test
+    #[arg(long, default_value_t = 0.05)]
+    pub alpha: f64,
+
+    /// Ignore slowdowns smaller than this many percent of the baseline median
+    #[arg(long, default_value_t = 5.0)]
+    pub threshold: f64,
+}
+
+#[derive(Debug, Args)]
+pub struct HistoryArgs {
+    /// Only show these days, either a single day (`7`) or a range (`3..9`, `3..=9`)
+    #[arg(value_parser = parse_days)]
+    pub days: Option<RangeInclusive<u8>>,
+
+    /// Timings appended by `run`
+    #[arg(long, default_value = "history.jsonl")]
+    pub file: PathBuf,
+
+    /// How many earlier timings make up the rolling baseline
+    #[arg(long, default_value_t = 5, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub window: usize,
+
+    /// Mark parts more than this many percent slower than their baseline
+    #[arg(long, default_value_t = 10.0)]
+    pub threshold: f64,
+}
+
+#[derive(Debug, Args)]
+pub struct FetchArgs {
+    /// Day to fetch, either a single day (`7`) or a range (`3..9`, `3..=9`)
+    #[arg(value_parser = parse_days)]
+    pub days: RangeInclusive<u8>,
+
+    /// Server to fetch from, overriding `base_url` in the config file and `AOC_BASE_URL`
+    #[arg(long)]
+    pub base_url: Option<String>,
+
+    /// Config file holding the `session` token, which `AOC_SESSION` overrides
+    #[arg(long, default_value = "aoc.toml")]
+    pub config: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct SubmitArgs {
+    pub day: u8,
+
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: u8,
+
+    /// Server to submit to, overriding `base_url` in the config file and `AOC_BASE_URL`
+    #[arg(long)]
+    pub base_url: Option<String>,
+
+    /// Config file holding the `session` token, which `AOC_SESSION` overrides
+    #[arg(long, default_value = "aoc.toml")]
+    pub config: PathBuf,
+
+    /// Every submitted answer and how it was judged
+    #[arg(long, default_value = "guesses.jsonl")]
+    pub log: PathBuf,
+
+    /// Known answers, which a correct answer is added to
+    #[arg(long, default_value = "answers.toml")]
+    pub answers: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct NewArgs {
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: u8,
+
+    /// Puzzle title shown in the runner's output
+    #[arg(long, default_value = "Untitled")]
+    pub title: String,
+}
+
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: u8,
+
+    /// Build in debug mode, which compiles faster but runs slower
+    #[arg(long)]
+    pub debug: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ReplArgs {
+    /// Day to start with
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: Option<u8>,
+
+    /// File that entered commands are kept in between sessions
+    #[arg(long, default_value = "repl-history.txt")]
+    pub history: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct AnimateArgs {
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: u8,
+
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: u8,
+
+    /// Where to write the animation: a `.gif`, an animated `.svg`, or
+    /// otherwise a directory of numbered PNGs
+    pub output: PathBuf,
+
+    /// Read the puzzle input from this file instead of `src/dayNN/input.txt`.
+    /// Without a path, or with `-`, the input is read from stdin
+    #[arg(long, short, num_args = 0..=1, default_missing_value = "-")]
+    pub input: Option<PathBuf>,
+
+    /// Frames shown per second
+    #[arg(long, default_value_t = 10)]
+    pub fps: u32,
+
+    /// Keep one step in this many, so long simulations stay short
+    #[arg(long, default_value_t = 1, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub every: usize,
+
+    /// Only draw this part of the map, as `x,y,width,height` in cells
+    #[arg(long, value_parser = parse_crop)]
+    pub crop: Option<Viewport>,
+
+    /// Stop recording after this many frames
+    #[arg(long, default_value_t = 1000)]
+    pub max_frames: usize,
+
+    /// Pixels to a cell
+    #[arg(long, default_value_t = 4)]
+    pub scale: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct Selection {
+    /// Day to run, either a single day (`7`) or a range (`3..9`, `3..=9`)
+    #[arg(value_parser = parse_days, required_unless_present = "all")]
+    pub days: Option<RangeInclusive<u8>>,
+
+    /// Run every day
+    #[arg(long, conflicts_with = "days")]
+    pub all: bool,
+
+    /// Only run the given part
+    #[arg(long, short, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    /// Read the puzzle input from this file instead of `src/dayNN/input.txt`.
+    /// Without a path, or with `-`, the input is read from stdin
+    #[arg(long, short, num_args = 0..=1, default_missing_value = "-")]
+    pub input: Option<PathBuf>,
+
+    /// Stop each part that runs longer than this, e.g. `500ms` or `2s`,
+    /// overriding the day's own limit
+    #[arg(long, value_parser = parse_duration)]
+    pub timeout: Option<Duration>,
+}
+
+impl RunArgs {
+    pub fn all() -> Self {
+        RunArgs {
+            selection: Selection {
+                days: None,
+                all: true,
+                part: None,
+                input: None,
+                timeout: None,
+            },
+            format: Format::Text,
+            answers: PathBuf::from("answers.toml"),
+            record: false,
+            parallel: false,
+            history: PathBuf::from("history.jsonl"),
+            no_history: false,
+        }
+    }
+}
+
+fn parse_day(s: &str) -> Result<u8, String> {
+    s.trim()
+        .parse::<u8>()
+        .map_err(|_| format!("`{s}` is not a day number"))
+}
+
+// Accepts `7`, `3..9` (end exclusive) and `3..=9` (end inclusive)
+fn parse_days(s: &str) -> Result<RangeInclusive<u8>, String> {
+    let range = if let Some((start, end)) = s.split_once("..=") {
+        parse_day(start)?..=parse_day(end)?
+    } else if let Some((start, end)) = s.split_once("..") {
+        let end = parse_day(end)?;
+        if end == 0 {
+            return Err(format!("`{s}` is an empty range"));
+        }
+        parse_day(start)?..=end - 1
+    } else {
+        let day = parse_day(s)?;
+        day..=day
+    };
+
+    if range.is_empty() {
+        return Err(format!("`{s}` is an empty range"));
+    }
+    if *range.start() < 1 || *range.end() > 25 {
+        return Err(format!("`{s}` is not within days 1 to 25"));
+    }
+
+    Ok(range)
+}
+
+// Accepts a number with a `ms` or `s` suffix
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let (number, scale) = if let Some(ms) = s.strip_suffix("ms") {
+        (ms, 0.001)
+    } else if let Some(secs) = s.strip_suffix('s') {
+        (secs, 1.0)
+    } else {
+        return Err(format!("`{s}` needs a unit, e.g. `500ms` or `2s`"));
+    };
+
+    number
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|n| *n > 0.0)
+        .map(|n| Duration::from_secs_f64(n * scale))
+        .ok_or_else(|| format!("`{s}` is not a positive duration"))
+}
+
+// Accepts `x,y,width,height`, in cells
+fn parse_crop(s: &str) -> Result<Viewport, String> {
+    let numbers = s
+        .split(',')
+        .map(|n| n.trim().parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("`{s}`: {e}"))?;
+    match numbers[..] {
+        [x, y, width, height] if width > 0 && height > 0 => Ok(Viewport {
+            x,
+            y,
+            width,
+            height,
+        }),
+        _ => Err(format!(
+            "`{s}` is not `x,y,width,height` with a non-empty area"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_ranges() {
+        assert_eq!(parse_days("7"), Ok(7..=7));
+        assert_eq!(parse_days("3..9"), Ok(3..=8));
+        assert_eq!(parse_days("3..=9"), Ok(3..=9));
+        assert!(parse_days("9..3").is_err());
+        assert!(parse_days("3..3").is_err());
+        assert!(parse_days("seven").is_err());
+        assert!(parse_days("0").is_err());
+        assert!(parse_days("20..=26").is_err());
+        assert_eq!(parse_days("20..26"), Ok(20..=25));
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("2s"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_duration("1.5s"), Ok(Duration::from_millis(1500)));
+        assert!(parse_duration("2").is_err());
+        assert!(parse_duration("-1s").is_err());
+    }
+
+    #[test]
+    fn crops() {
+        assert_eq!(
+            parse_crop("10, 5,40,20"),
+            Ok(Viewport {
+                x: 10,
+                y: 5,
+                width: 40,
+                height: 20
+            })
+        );
+        assert!(parse_crop("10,5,40").is_err());
+        assert!(parse_crop("10,5,0,20").is_err());
+        assert!(parse_crop("-1,5,40,20").is_err());
+    }
+}
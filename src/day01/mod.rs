@@ -1,16 +1,52 @@
-use std::fs;
+use crate::{
+    error::{offset_in, AocError},
+    parsing,
+    solution::{Answer, Solution},
+};
+use tracing::trace;
 
-pub fn part_one(file: &str) -> i32 {
-    let contents = fs::read_to_string(file).expect("Should have been able to read input.txt");
+pub struct Day01;
 
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Basic list sorting";
+
+    type Input = (Vec<i32>, Vec<i32>);
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part_one(input).into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part_two(input).into())
+    }
+}
+
+pub fn parse(contents: &str) -> Result<(Vec<i32>, Vec<i32>), AocError> {
     let mut left = Vec::new();
     let mut right = Vec::new();
     for line in contents.lines() {
-        let mut elems = line.split_whitespace();
-        left.push(elems.next().unwrap().parse::<i32>().unwrap());
-        right.push(elems.next().unwrap().parse::<i32>().unwrap());
+        let [l, r] = parsing::words(contents, line)?[..] else {
+            return Err(AocError::parse_at(
+                contents,
+                offset_in(contents, line),
+                "expected two location IDs",
+            ));
+        };
+        left.push(l);
+        right.push(r);
     }
 
+    Ok((left, right))
+}
+
+pub fn part_one(lists: &(Vec<i32>, Vec<i32>)) -> i32 {
+    let (mut left, mut right) = lists.clone();
+
     left.sort();
     right.sort();
 
@@ -21,23 +57,13 @@ pub fn part_one(file: &str) -> i32 {
     for pair in pairs {
         let difference = pair.0 - pair.1;
         differences += difference.abs();
-        //println!("{:?} difference {difference}, differences: {differences}", pair);
+        trace!("{:?} difference {difference}, differences: {differences}", pair);
     }
 
     differences
 }
 
-pub fn part_two(file: &str) -> i32 {
-    let contents = fs::read_to_string(file).expect("Should have been able to read input.txt");
-
-    let mut left = Vec::new();
-    let mut right = Vec::new();
-    for line in contents.lines() {
-        let mut elems = line.split_whitespace();
-        left.push(elems.next().unwrap().parse::<i32>().unwrap());
-        right.push(elems.next().unwrap().parse::<i32>().unwrap());
-    }
-
+pub fn part_two((left, right): &(Vec<i32>, Vec<i32>)) -> i32 {
     let mut similarity = 0;
 
     for value in left.iter() {
@@ -58,14 +84,10 @@ mod tests {
     use super::*;
 
     #[test]
-    fn p1() {
-        let result = part_one(&"src/day01/test.txt");
-        assert_eq!(result, 11);
-    }
-
-    #[test]
-    fn p2() {
-        let result = part_two(&"src/day01/test.txt");
-        assert_eq!(result, 31);
+    fn bad_input() {
+        let err = parse("3   4\n4   x\n").unwrap_err();
+        assert!(err.to_string().starts_with("parse error at line 2, column 5"));
+        let err = parse("3   4\n4\n").unwrap_err();
+        assert!(err.to_string().starts_with("parse error at line 2, column 1"));
     }
 }
@@ -1,4 +1,30 @@
-use std::fs;
+use crate::{
+    error::AocError,
+    parsing,
+    solution::{Answer, Solution},
+};
+use tracing::debug;
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Order parsing with dropout";
+
+    type Input = Vec<Vec<i32>>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part_one(input).into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part_two(input).into())
+    }
+}
 
 fn difference_is_ok(current: &i32, previous: &i32) -> bool {
     let difference = current - previous;
@@ -9,16 +35,7 @@ fn difference_is_ok(current: &i32, previous: &i32) -> bool {
     true
 }
 
-fn parse_report(report: &str) -> Vec<i32> {
-    let mut parsed = Vec::new();
-    for level in report.split(" ") {
-        parsed.push(level.parse::<i32>().unwrap());
-    }
-
-    parsed
-}
-
-fn first_bad_index(report: &Vec<i32>) -> Option<usize> {
+fn first_bad_index(report: &[i32]) -> Option<usize> {
     let mut previous_level: Option<&i32> = None;
     let mut ascending_state: Option<bool> = None;
 
@@ -64,14 +81,18 @@ fn first_bad_index(report: &Vec<i32>) -> Option<usize> {
     None
 }
 
-pub fn part_one(file: &str) -> i32 {
-    let content = fs::read_to_string(file).expect("Should read");
+pub fn parse(content: &str) -> Result<Vec<Vec<i32>>, AocError> {
+    content
+        .lines()
+        .map(|report| parsing::words(content, report))
+        .collect()
+}
 
+pub fn part_one(reports: &[Vec<i32>]) -> i32 {
     let mut safe_reports = 0;
 
-    for report_raw in content.lines() {
-        let report = parse_report(report_raw);
-        let unsafe_index = first_bad_index(&report);
+    for report in reports {
+        let unsafe_index = first_bad_index(report);
         match unsafe_index {
             Some(_) => {}
             None => safe_reports += 1,
@@ -81,15 +102,12 @@ pub fn part_one(file: &str) -> i32 {
     safe_reports
 }
 
-pub fn part_two(file: &str) -> i32 {
-    let content = fs::read_to_string(file).expect("Should read");
-
+pub fn part_two(reports: &[Vec<i32>]) -> i32 {
     let mut safe_reports = 0;
 
-    for report_raw in content.lines() {
-        let report = parse_report(report_raw);
+    for report in reports {
         // first check if there's a bad level and remove it
-        let unsafe_index = first_bad_index(&report);
+        let unsafe_index = first_bad_index(report);
         match unsafe_index {
             Some(index) => {
                 // Need to try removing index and index-1 as either one may be the culprit
@@ -100,14 +118,14 @@ pub fn part_two(file: &str) -> i32 {
                 }
                 for i in try_remove {
                     let mut report_cloned = report.clone();
-                    let _removed = report_cloned.remove(i);
+                    let removed = report_cloned.remove(i);
                     let still_unsafe = first_bad_index(&report_cloned);
                     match still_unsafe {
                         Some(_) => {
-                            //println!("{report_raw}: Unsafe even after removing position {i}, {removed}.");
+                            debug!("{report:?}: Unsafe even after removing position {i}, {removed}.");
                         }
                         None => {
-                            //println!("{report_raw}: Safe by removing level {i}, {removed}");
+                            debug!("{report:?}: Safe by removing level {i}, {removed}");
                             safe_reports += 1;
                             break;
                         }
@@ -115,7 +133,7 @@ pub fn part_two(file: &str) -> i32 {
                 }
             }
             None => {
-                //println!("{report_raw}: Safe without removing any level.");
+                debug!("{report:?}: Safe without removing any level.");
                 safe_reports += 1;
             }
         }
@@ -123,20 +141,3 @@ pub fn part_two(file: &str) -> i32 {
 
     safe_reports
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn p1() {
-        let result = part_one(&"src/day02/test.txt");
-        assert_eq!(result, 2);
-    }
-
-    #[test]
-    fn p2() {
-        let result = part_two(&"src/day02/test.txt");
-        assert_eq!(result, 4);
-    }
-}
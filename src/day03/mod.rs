@@ -1,20 +1,47 @@
+use crate::{
+    error::{parse_token, AocError},
+    solution::{Answer, Solution},
+};
 use regex::{Captures, Regex};
-use std::fs;
 
-pub fn part_one(file: &str) -> i32 {
-    let content = fs::read_to_string(file).expect("Should read");
+pub struct Day03;
 
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Regexing multiple captures";
+
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        Ok(parse(input))
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer, AocError> {
+        part_one(input).map(Answer::from)
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Answer, AocError> {
+        part_two(input).map(Answer::from)
+    }
+}
+
+// Both parts scan the raw memory with different patterns
+pub fn parse(content: &str) -> String {
+    content.to_string()
+}
+
+pub fn part_one(content: &str) -> Result<i32, AocError> {
     let re = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
 
     let mut total = 0;
 
-    for capture in re.captures_iter(&content) {
-        let a = &capture[1].parse::<i32>().unwrap();
-        let b = &capture[2].parse::<i32>().unwrap();
+    for capture in re.captures_iter(content) {
+        let a: i32 = parse_token(content, &capture[1])?;
+        let b: i32 = parse_token(content, &capture[2])?;
         total += a * b;
     }
 
-    total
+    Ok(total)
 }
 
 #[derive(Debug)]
@@ -24,33 +51,31 @@ enum Match {
     Dont {},
 }
 
-impl From<Captures<'_>> for Match {
-    fn from(capture: Captures<'_>) -> Self {
-        if let Some(_) = capture.name("mul") {
-            Match::Mul {
-                a: capture[2].parse::<i32>().unwrap(),
-                b: capture[3].parse::<i32>().unwrap(),
-            }
-        } else if let Some(_) = capture.name("do") {
-            Match::Do {}
-        } else if let Some(_) = capture.name("dont") {
-            Match::Dont {}
+impl Match {
+    fn new(content: &str, capture: Captures<'_>) -> Result<Self, AocError> {
+        if capture.name("mul").is_some() {
+            Ok(Match::Mul {
+                a: parse_token(content, &capture[2])?,
+                b: parse_token(content, &capture[3])?,
+            })
+        } else if capture.name("do").is_some() {
+            Ok(Match::Do {})
+        } else if capture.name("dont").is_some() {
+            Ok(Match::Dont {})
         } else {
-            panic!("unknown match captured!")
+            Err(AocError::invalid("unknown match captured!"))
         }
     }
 }
 
-pub fn part_two(file: &str) -> i32 {
-    let content = fs::read_to_string(file).expect("Should read");
-
+pub fn part_two(content: &str) -> Result<i32, AocError> {
     let re = Regex::new(r"(?P<mul>mul\((\d+),(\d+)\))|(?P<do>do\(\))|(?P<dont>don't\(\))").unwrap();
 
     let mut total = 0;
     let mut enabled = true;
 
-    for capture in re.captures_iter(&content) {
-        let m: Match = capture.into();
+    for capture in re.captures_iter(content) {
+        let m = Match::new(content, capture)?;
 
         match m {
             Match::Mul { a, b } => {
@@ -70,22 +95,5 @@ pub fn part_two(file: &str) -> i32 {
             }
         }
     }
-    total
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn p1() {
-        let result = part_one(&"src/day03/test.txt");
-        assert_eq!(result, 161);
-    }
-
-    #[test]
-    fn p2() {
-        let result = part_two(&"src/day03/test.txt");
-        assert_eq!(result, 48);
-    }
+    Ok(total)
 }
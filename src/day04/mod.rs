@@ -1,104 +1,60 @@
-use std::fs;
+use crate::{
+    error::AocError,
+    geometry::{Dir8, Vec2},
+    grid::{Grid, Pos},
+    parsing,
+    solution::{Answer, Solution},
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Position {
-    row: i32,
-    col: i32,
-}
+pub struct Day04;
 
-impl Position {
-    fn get_center_position(&self, direction: Direction) -> Position {
-        // Get the position of the 'A' (second letter) in MAS
-        Position {
-            row: self.row + direction.row_delta,
-            col: self.col + direction.col_delta,
-        }
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "2d word lookup";
+
+    type Input = WordFinder;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
     }
-}
 
-#[derive(Debug, Clone, Copy)]
-struct Direction {
-    row_delta: i32,
-    col_delta: i32,
+    fn part_one(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part_one(input).into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part_two(input).into())
+    }
 }
 
+/// Where a word starts in the grid and which way it reads.
 #[derive(Debug)]
-struct WordLocation {
-    start: Position,
-    direction: Direction,
+pub struct WordLocation {
+    start: Pos,
+    direction: Dir8,
+}
+
+impl WordLocation {
+    fn center(&self) -> Vec2 {
+        // The position of the 'A' (second letter) in MAS
+        Vec2::from(self.start) + self.direction.delta()
+    }
 }
 
-struct WordFinder {
-    grid: Vec<Vec<char>>,
-    height: i32,
-    width: i32,
-    directions: Vec<Direction>,
+pub struct WordFinder {
+    grid: Grid<char>,
 }
 
 impl WordFinder {
-    fn new(grid: Vec<Vec<char>>) -> Self {
-        let height = grid.len() as i32;
-        let width = if height > 0 { grid[0].len() as i32 } else { 0 };
-
-        // Define all 8 possible directions
-        let directions = vec![
-            Direction {
-                row_delta: 0,
-                col_delta: 1,
-            }, // right
-            Direction {
-                row_delta: 1,
-                col_delta: 1,
-            }, // down-right
-            Direction {
-                row_delta: 1,
-                col_delta: 0,
-            }, // down
-            Direction {
-                row_delta: 1,
-                col_delta: -1,
-            }, // down-left
-            Direction {
-                row_delta: 0,
-                col_delta: -1,
-            }, // left
-            Direction {
-                row_delta: -1,
-                col_delta: -1,
-            }, // up-left
-            Direction {
-                row_delta: -1,
-                col_delta: 0,
-            }, // up
-            Direction {
-                row_delta: -1,
-                col_delta: 1,
-            }, // up-right
-        ];
-
-        WordFinder {
-            grid,
-            height,
-            width,
-            directions,
-        }
-    }
-
-    fn find_word(&self, word: &str) -> Vec<WordLocation> {
+    pub fn find_word(&self, word: &str) -> Vec<WordLocation> {
         let word: Vec<char> = word.to_uppercase().chars().collect();
         let mut results = Vec::new();
 
-        // Try each starting position
-        for row in 0..self.height {
-            for col in 0..self.width {
-                // Try each direction
-                for &direction in &self.directions {
-                    if self.check_word(&word, Position { row, col }, direction) {
-                        results.push(WordLocation {
-                            start: Position { row, col },
-                            direction,
-                        });
-                    }
+        // Try each starting position in each direction
+        for start in self.grid.positions() {
+            for direction in Dir8::ALL {
+                if self.check_word(&word, start, direction) {
+                    results.push(WordLocation { start, direction });
                 }
             }
         }
@@ -106,51 +62,28 @@ impl WordFinder {
         results
     }
 
-    fn check_word(&self, word: &[char], start: Position, direction: Direction) -> bool {
-        let word_len = word.len() as i32;
-
-        // Check if word would go out of bounds
-        let end_row = start.row + (word_len - 1) * direction.row_delta;
-        let end_col = start.col + (word_len - 1) * direction.col_delta;
-
-        if end_row < 0 || end_row >= self.height || end_col < 0 || end_col >= self.width {
-            return false;
-        }
-
-        // Check each character
-        let mut curr_pos = start;
-        for &char in word {
-            if self.grid[curr_pos.row as usize][curr_pos.col as usize] != char {
-                return false;
-            }
-            curr_pos = Position {
-                row: curr_pos.row + direction.row_delta,
-                col: curr_pos.col + direction.col_delta,
-            };
-        }
-
-        true
+    fn check_word(&self, word: &[char], start: Pos, direction: Dir8) -> bool {
+        let mut ray = self.grid.ray(start, direction.delta());
+        word.iter()
+            .all(|c| ray.next().is_some_and(|(_, cell)| cell == c))
     }
 }
 
-fn find_intersections(locations: &[WordLocation]) -> Vec<Position> {
+fn find_intersections(locations: &[WordLocation]) -> Vec<Vec2> {
     let mut intersection_points = Vec::new();
 
     // Filter to only diagonal directions
     let diagonal_locations: Vec<&WordLocation> = locations
         .iter()
-        .filter(|loc| {
-            let d = loc.direction;
-            d.row_delta.abs() == 1 && d.col_delta.abs() == 1
-        })
+        .filter(|loc| loc.direction.is_diagonal())
         .collect();
 
     // Compare each pair of diagonal MAS
     for (i, loc1) in diagonal_locations.iter().enumerate() {
         for loc2 in diagonal_locations.iter().skip(i + 1) {
             // Get the position of the 'A' in each CAT
-            let a1 = loc1.start.get_center_position(loc1.direction);
-            let a2 = loc2.start.get_center_position(loc2.direction);
+            let a1 = loc1.center();
+            let a2 = loc2.center();
 
             // If the A's overlap, this is an intersection point
             if a1 == a2 {
@@ -160,64 +93,28 @@ fn find_intersections(locations: &[WordLocation]) -> Vec<Position> {
     }
 
     // Remove duplicates
-    intersection_points.sort_by_key(|p| (p.row, p.col));
+    intersection_points.sort();
     intersection_points.dedup();
 
     intersection_points
 }
 
-fn prepare_grid(content: String) -> Vec<Vec<char>> {
-    let mut grid = Vec::new();
-
-    for row_raw in content.lines() {
-        let mut row = Vec::new();
-        for c in row_raw.split("") {
-            if c == "" {
-                continue;
-            }
-            row.push(c.chars().next().expect("Should be a char"));
-        }
-        grid.push(row);
-    }
-
-    grid
+pub fn parse(content: &str) -> Result<WordFinder, AocError> {
+    Ok(WordFinder {
+        grid: parsing::char_grid(content, content)?,
+    })
 }
 
-pub fn part_one(file: &str) -> usize {
-    let content = fs::read_to_string(file).expect("Should read");
-
-    let word_finder = WordFinder::new(prepare_grid(content));
-
+pub fn part_one(word_finder: &WordFinder) -> usize {
     let results = word_finder.find_word("XMAS");
 
     results.len()
 }
 
-pub fn part_two(file: &str) -> usize {
-    let content = fs::read_to_string(file).expect("Should read");
-
-    let word_finder = WordFinder::new(prepare_grid(content));
-
+pub fn part_two(word_finder: &WordFinder) -> usize {
     let results = word_finder.find_word("MAS");
 
     let intersections = find_intersections(&results);
 
     intersections.len()
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn p1() {
-        let result = part_one(&"src/day04/test.txt");
-        assert_eq!(result, 18);
-    }
-
-    #[test]
-    fn p2() {
-        let result = part_two(&"src/day04/test.txt");
-        assert_eq!(result, 9);
-    }
-}
@@ -1,46 +1,74 @@
-use std::{cmp::Ordering, fs};
+use crate::{
+    error::{offset_in, AocError},
+    parsing,
+    solution::{Answer, Solution},
+};
+use std::{cmp::Ordering, collections::HashSet};
+use tracing::debug;
 
-fn prepare_data(content: &str) -> (Vec<Vec<&str>>, Vec<&str>) {
-    let Some((ordering_raw, updates_raw)) = content.split_once("\n\n") else {
-        panic!("bad split")
-    };
+pub struct Day05;
 
-    let mut ordering = Vec::new();
-    for pair in ordering_raw.split("\n") {
-        ordering.push(pair);
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "Unusual ordering and sort";
+
+    type Input = PrintQueue;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
     }
 
-    let mut updates = Vec::new();
-    for update_raw in updates_raw.split("\n") {
-        if update_raw == "" {
-            continue;
-        }
+    fn part_one(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part_one(input).into())
+    }
 
-        let mut numbers = Vec::new();
-        for number in update_raw.split(",") {
-            numbers.push(number);
-        }
+    fn part_two(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part_two(input).into())
+    }
+}
+
+pub struct PrintQueue {
+    ordering: HashSet<(i32, i32)>,
+    updates: Vec<Vec<i32>>,
+}
 
-        updates.push(numbers);
+pub fn parse(content: &str) -> Result<PrintQueue, AocError> {
+    let (ordering_raw, updates_raw) =
+        parsing::two_sections(content, "the ordering rules and the updates")?;
+
+    let mut ordering = HashSet::new();
+    for pair in ordering_raw.lines() {
+        let [first, last] = parsing::separated(content, pair, '|')?[..] else {
+            return Err(AocError::parse_at(
+                content,
+                offset_in(content, pair),
+                "Ordering rules are pairs",
+            ));
+        };
+        ordering.insert((first, last));
+    }
+
+    let mut updates = Vec::new();
+    for update_raw in updates_raw.lines() {
+        updates.push(parsing::separated(content, update_raw, ',')?);
     }
 
-    (updates, ordering)
+    Ok(PrintQueue { ordering, updates })
 }
 
-fn pair_is_valid(first: &str, last: &str, ordering: &Vec<&str>) -> bool {
-    let invalid_pair = format!("{}|{}", last, first);
-    if ordering.contains(&invalid_pair.as_str()) {
-        //println!("invalid pair found {:?}", invalid_pair);
+fn pair_is_valid(first: i32, last: i32, ordering: &HashSet<(i32, i32)>) -> bool {
+    if ordering.contains(&(last, first)) {
+        debug!("invalid pair found {:?}", (last, first));
         return false;
     }
     true
 }
 
-fn order_is_valid(mut numbers: Vec<&str>, ordering: &Vec<&str>) -> bool {
+fn order_is_valid(mut numbers: Vec<i32>, ordering: &HashSet<(i32, i32)>) -> bool {
     numbers.reverse();
     while let Some(this_number) = numbers.pop() {
-        for later_number in &mut *numbers {
-            if !pair_is_valid(this_number, later_number, ordering) {
+        for later_number in &numbers {
+            if !pair_is_valid(this_number, *later_number, ordering) {
                 return false;
             }
         }
@@ -48,49 +76,40 @@ fn order_is_valid(mut numbers: Vec<&str>, ordering: &Vec<&str>) -> bool {
     true
 }
 
-fn get_middle_sum(updates: Vec<Vec<&str>>) -> i32 {
+fn get_middle_sum(updates: Vec<Vec<i32>>) -> i32 {
     let mut middle_sum = 0;
     for update in updates {
-        let middle_raw = update[update.len() / 2];
-        middle_sum += middle_raw.parse::<i32>().unwrap();
+        middle_sum += update[update.len() / 2];
     }
     middle_sum
 }
 
-pub fn part_one(file: &str) -> i32 {
-    let content = fs::read_to_string(file).expect("Should read");
-
-    let (updates, ordering) = prepare_data(content.as_str());
-
+pub fn part_one(queue: &PrintQueue) -> i32 {
     let mut correct_updates = Vec::new();
-    for update in updates {
-        if order_is_valid(update.clone(), &ordering) {
-            correct_updates.push(update);
+    for update in &queue.updates {
+        if order_is_valid(update.clone(), &queue.ordering) {
+            correct_updates.push(update.clone());
         }
     }
 
     get_middle_sum(correct_updates)
 }
 
-pub fn part_two(file: &str) -> i32 {
-    let content = fs::read_to_string(file).expect("Should read");
-
-    let (updates, ordering) = prepare_data(content.as_str());
-
+pub fn part_two(queue: &PrintQueue) -> i32 {
     let mut incorrect_updates = Vec::new();
-    for update in updates {
-        if !order_is_valid(update.clone(), &ordering) {
-            incorrect_updates.push(update);
+    for update in &queue.updates {
+        if !order_is_valid(update.clone(), &queue.ordering) {
+            incorrect_updates.push(update.clone());
         }
     }
 
     let mut sorted_updates = Vec::new();
     for mut update in incorrect_updates.clone() {
         update.sort_by(|first, last| {
-            if pair_is_valid(first, last, &ordering) {
-                return Ordering::Less;
+            if pair_is_valid(*first, *last, &queue.ordering) {
+                Ordering::Less
             } else {
-                return Ordering::Greater;
+                Ordering::Greater
             }
         });
         sorted_updates.push(update);
@@ -98,20 +117,3 @@ pub fn part_two(file: &str) -> i32 {
 
     get_middle_sum(sorted_updates)
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn p1() {
-        let result = part_one(&"src/day05/test.txt");
-        assert_eq!(result, 143);
-    }
-
-    #[test]
-    fn p2() {
-        let result = part_two(&"src/day05/test.txt");
-        assert_eq!(result, 123);
-    }
-}
@@ -1,273 +1,200 @@
-use std::{collections::HashSet, fmt, fs};
+use crate::{
+    animate, deadline,
+    error::AocError,
+    geometry::Dir4,
+    grid::{Grid, Pos},
+    parsing,
+    render::{self, Cell, Layer, Render, Rgb, Viewport},
+    solution::{Answer, Solution},
+};
+use std::{collections::HashSet, fmt, time::Duration};
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Path finding";
+    const TIMEOUT: Option<Duration> = Some(Duration::from_secs(60));
+
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
+    }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct Point {
-    x: i32,
-    y: i32,
-    obstacle: bool,
-}
+    fn part_one(input: &Self::Input) -> Result<Answer, AocError> {
+        part_one(input).map(Answer::from)
+    }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+    fn part_two(input: &Self::Input) -> Result<Answer, AocError> {
+        part_two(input).map(Answer::from)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct Cursor {
-    x: i32,
-    y: i32,
-    direction: Direction,
-}
-
-impl Cursor {
-    fn turn(&mut self) {
-        match self.direction {
-            Direction::Up => self.direction = Direction::Right,
-            Direction::Right => self.direction = Direction::Down,
-            Direction::Down => self.direction = Direction::Left,
-            Direction::Left => self.direction = Direction::Up,
-        }
-    }
+    pos: Pos,
+    direction: Dir4,
 }
 
 #[derive(Debug, Clone)]
-struct Map {
-    grid: Vec<Vec<Point>>,
+pub struct Map {
+    // true where there is an obstacle
+    grid: Grid<bool>,
     cursor: Cursor,
-    marked_points: HashSet<Point>,
+    marked_points: HashSet<Pos>,
 }
 
-impl fmt::Display for Map {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let half_window = 20;
-
-        let start_row = (self.cursor.y - half_window).max(0) as usize;
-        let end_row = (self.cursor.y + half_window).min(self.grid.len() as i32 - 1) as usize;
-
-        let row_width = self.grid[0].len();
-        let start_col = (self.cursor.x - half_window).max(0) as usize;
-        let end_col = (self.cursor.x + half_window).min(row_width as i32 - 1) as usize;
-
-        for y in start_row..=end_row {
-            if let Some(row) = self.grid.get(y) {
-                for x in start_col..=end_col {
-                    if self.marked_points.contains(&Point {
-                        x: x as i32,
-                        y: y as i32,
-                        obstacle: false,
-                    }) {
-                        write!(f, "\x1b[32mO\x1b[0m")?;
-                    } else if x as i32 == self.cursor.x && y as i32 == self.cursor.y {
-                        match self.cursor.direction {
-                            Direction::Up => write!(f, "\x1b[31m↑\x1b[0m")?,
-                            Direction::Down => write!(f, "\x1b[31m↓\x1b[0m")?,
-                            Direction::Left => write!(f, "\x1b[31m←\x1b[0m")?,
-                            Direction::Right => write!(f, "\x1b[31m→\x1b[0m")?,
-                        }
-                    } else if row[x].obstacle {
-                        write!(f, "#")?;
-                    } else {
-                        write!(f, "\x1b[90m·\x1b[0m")?;
-                    }
-                }
-                writeln!(f)?;
-            }
+impl Render for Map {
+    fn size(&self) -> (usize, usize) {
+        (self.grid.width(), self.grid.height())
+    }
+
+    fn cell(&self, pos: Pos) -> Cell {
+        if self.marked_points.contains(&pos) {
+            Cell::new('O', Layer::Highlight).with_color(Rgb::GREEN)
+        } else if pos == self.cursor.pos {
+            let arrow = match self.cursor.direction {
+                Dir4::Up => '↑',
+                Dir4::Down => '↓',
+                Dir4::Left => '←',
+                Dir4::Right => '→',
+            };
+            Cell::new(arrow, Layer::Entity).with_color(Rgb::RED)
+        } else if self.grid[pos] {
+            Cell::new('#', Layer::Terrain)
+        } else {
+            Cell::new('·', Layer::Background)
         }
+    }
+}
 
-        Ok(())
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let view = Viewport::around(self.cursor.pos, 20);
+        write!(f, "{}", render::ansi(self, Some(view)))
     }
 }
 
 impl Map {
-    fn new(content: &str) -> Self {
-        let mut grid = Vec::new();
-
-        let mut cursor: Option<Cursor> = None;
-
-        for (y, line) in content.lines().enumerate() {
-            let mut row = Vec::new();
-
-            for (x, c) in line.split("").enumerate() {
-                if c == "^" {
-                    cursor = Some(Cursor {
-                        x: x.try_into().unwrap(),
-                        y: y.try_into().unwrap(),
-                        direction: Direction::Up,
-                    })
-                } else if c == "v" {
-                    cursor = Some(Cursor {
-                        x: x.try_into().unwrap(),
-                        y: y.try_into().unwrap(),
-                        direction: Direction::Down,
-                    })
-                } else if c == ">" {
-                    cursor = Some(Cursor {
-                        x: x.try_into().unwrap(),
-                        y: y.try_into().unwrap(),
-                        direction: Direction::Right,
-                    })
-                } else if c == "<" {
-                    cursor = Some(Cursor {
-                        x: x.try_into().unwrap(),
-                        y: y.try_into().unwrap(),
-                        direction: Direction::Left,
-                    })
-                }
+    fn new(content: &str) -> Result<Self, AocError> {
+        let tiles = parsing::char_grid(content, content)?;
 
-                row.push(Point {
-                    x: x.try_into().unwrap(),
-                    y: y.try_into().unwrap(),
-                    obstacle: if c == "#" { true } else { false },
-                })
-            }
-
-            grid.push(row);
-        }
+        let (pos, direction) = tiles
+            .iter()
+            .find_map(|(pos, c)| Some((pos, Dir4::from_arrow(*c)?)))
+            .ok_or_else(|| AocError::invalid("No Cursor encountered"))?;
 
-        Map {
-            grid,
-            cursor: cursor.expect("No Cursor encountered"),
+        Ok(Map {
+            grid: tiles.map(|c| *c == '#'),
+            cursor: Cursor { pos, direction },
             marked_points: HashSet::new(),
-        }
+        })
     }
 
-    // Take next step, returning the next point the cursor is on
-    fn next(&mut self) -> Option<Point> {
-        let (next_x, next_y) = match self.cursor.direction {
-            Direction::Up => (self.cursor.x, self.cursor.y - 1),
-            Direction::Down => (self.cursor.x, self.cursor.y + 1),
-            Direction::Left => (self.cursor.x - 1, self.cursor.y),
-            Direction::Right => (self.cursor.x + 1, self.cursor.y),
-        };
-
-        match self
-            .grid
-            .get(next_y as usize)
-            .and_then(|row| row.get(next_x as usize))
-            .cloned()
-        {
-            Some(next_point) if next_point.obstacle => {
-                self.cursor.turn();
-                self.next()
-            }
-            Some(next_point) => {
-                self.cursor.x = next_x;
-                self.cursor.y = next_y;
-                Some(next_point)
+    // Take next step, returning the next point the cursor is on, or None once
+    // it leaves the map
+    fn next(&mut self) -> Result<Option<Pos>, AocError> {
+        // after four turns the cursor has faced every way
+        for _ in 0..4 {
+            let Some(next) = self
+                .grid
+                .step(self.cursor.pos, self.cursor.direction.delta())
+            else {
+                return Ok(None);
+            };
+
+            if self.grid[next] {
+                self.cursor.direction = self.cursor.direction.turn_right();
+            } else {
+                self.cursor.pos = next;
+                return Ok(Some(next));
             }
-            None => None,
         }
+        Err(AocError::invalid("guard is boxed in"))
     }
 
-    fn simulate_obstruction(&mut self, patrolled_points: &HashSet<Point>) -> Option<Point> {
+    fn simulate_obstruction(&mut self, patrolled_points: &HashSet<Pos>) -> Option<Pos> {
         // Add new obstruction point at the current cursor
-        let new_obstacle = match self
-            .grid
-            .get(self.cursor.y as usize)
-            .and_then(|row| row.get(self.cursor.x as usize))
-            .cloned()
-        {
-            Some(point) => {
-                if !point.obstacle {
-                    Some(point)
-                } else {
-                    None
-                }
-            }
-            None => None,
-        };
+        let new_obstacle = self.cursor.pos;
+        if self.grid[new_obstacle] {
+            return None;
+        }
 
         // Can't place on starting position
-        if patrolled_points.contains(&new_obstacle.clone().unwrap()) {
+        if patrolled_points.contains(&new_obstacle) {
             return None;
         }
 
         // Update the point in the map to actually be an obstacle
-        let check_point = self
-            .grid
-            .get_mut(self.cursor.y as usize)
-            .and_then(|row| row.get_mut(self.cursor.x as usize))
-            .unwrap();
-        check_point.obstacle = true;
+        self.grid[new_obstacle] = true;
 
         // backup 1 step depending on direction, then simulate with added obstacle
-        match self.cursor.direction {
-            Direction::Up => self.cursor.y += 1,
-            Direction::Down => self.cursor.y -= 1,
-            Direction::Left => self.cursor.x += 1,
-            Direction::Right => self.cursor.x -= 1,
-        };
+        let back = self.cursor.direction.turn_around();
+        self.cursor.pos = self.grid.step(new_obstacle, back.delta())?;
 
         // if we return to a cursor position we've already seen during
         // simulation, we're in an infinite loop
         let mut simulated_cursors = HashSet::new();
         simulated_cursors.insert(self.cursor.clone());
 
-        while let Some(_) = self.next() {
-            if simulated_cursors.contains(&self.cursor) {
-                return new_obstacle;
-            } else {
-                simulated_cursors.insert(self.cursor.clone());
+        loop {
+            match self.next() {
+                Ok(Some(_)) => {
+                    if simulated_cursors.contains(&self.cursor) {
+                        return Some(new_obstacle);
+                    } else {
+                        simulated_cursors.insert(self.cursor.clone());
+                    }
+                }
+                // we left the map
+                Ok(None) => return None,
+                // boxed in by the new obstacle, so the guard never leaves either
+                Err(_) => return Some(new_obstacle),
             }
         }
-
-        // if we get out of the loop, we left the map
-        None
     }
 }
 
-fn deduplicate_points(points: Vec<Point>) -> Vec<Point> {
-    let set: HashSet<_> = points.into_iter().collect();
-    set.into_iter().collect()
+pub fn parse(content: &str) -> Result<Map, AocError> {
+    Map::new(content)
 }
 
-pub fn part_one(file: &str) -> usize {
-    let content = fs::read_to_string(file).expect("Should read");
-
-    let mut map = Map::new(content.as_str());
+pub fn part_one(map: &Map) -> Result<usize, AocError> {
+    let mut map = map.clone();
 
-    let mut patrolled_points = Vec::new();
-    patrolled_points.push(map.grid[map.cursor.y as usize][map.cursor.x as usize].clone());
+    let mut patrolled_points = HashSet::new();
+    patrolled_points.insert(map.cursor.pos);
+    animate::capture(|| &map);
 
-    while let Some(patrolled_point) = map.next() {
-        patrolled_points.push(patrolled_point);
+    while let Some(patrolled_point) = map.next()? {
+        patrolled_points.insert(patrolled_point);
+        animate::capture(|| &map);
     }
 
-    deduplicate_points(patrolled_points).len()
+    Ok(patrolled_points.len())
 }
 
-pub fn part_two(file: &str) -> usize {
-    let content = fs::read_to_string(file).expect("Should read");
-
-    let mut map = Map::new(content.as_str());
-
-    let cursor_start = map.cursor.clone();
+pub fn part_two(map: &Map) -> Result<usize, AocError> {
+    let mut map = map.clone();
 
     // Record the start position so we don't try to place an obstacle there in the future
     let mut patrolled_points = HashSet::new();
-    patrolled_points.insert(Point {
-        x: cursor_start.x,
-        y: cursor_start.y,
-        obstacle: false,
-    });
+    patrolled_points.insert(map.cursor.pos);
 
     // then iterate over each step, checking if adding an obstruction after the step makes a loop
     // keep track of positions we've walked on so we don't place obstacles there if we ever return
     let mut patrolled_points = HashSet::new();
-    while let Some(patrolled_point) = map.next() {
-        match map.clone().simulate_obstruction(&patrolled_points) {
-            Some(obstruction_at) => {
-                map.marked_points.insert(obstruction_at);
-            }
-            None => {}
-        };
+    while let Some(patrolled_point) = map.next()? {
+        deadline::check()?;
+        if let Some(obstruction_at) = map.clone().simulate_obstruction(&patrolled_points) {
+            map.marked_points.insert(obstruction_at);
+        }
         patrolled_points.insert(patrolled_point);
+        animate::capture(|| &map);
     }
 
-    map.marked_points.len()
+    Ok(map.marked_points.len())
 }
 
 #[cfg(test)]
@@ -275,14 +202,16 @@ mod tests {
     use super::*;
 
     #[test]
-    fn p1() {
-        let result = part_one(&"src/day06/test.txt");
-        assert_eq!(result, 41);
+    fn needs_a_guard() {
+        let err = parse("..#\n...\n").unwrap_err();
+        assert_eq!(err.to_string(), "invalid puzzle: No Cursor encountered");
     }
 
     #[test]
-    fn p2() {
-        let result = part_two(&"src/day06/test.txt");
-        assert_eq!(result, 6);
+    fn boxed_in_guard() {
+        let map = parse(".#.\n#^#\n.#.\n").unwrap();
+        let err = part_one(&map).unwrap_err();
+        assert_eq!(err.to_string(), "invalid puzzle: guard is boxed in");
+        assert!(part_two(&map).is_err());
     }
 }
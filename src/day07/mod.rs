@@ -1,38 +1,83 @@
+use crate::{
+    deadline,
+    error::{offset_in, AocError},
+    parsing,
+    solution::{Answer, Solution},
+};
 use rayon::prelude::*;
-use std::fs;
+use std::{collections::VecDeque, time::Duration};
 
 mod part1;
 mod part2;
 
-pub fn part_one(file: &str) -> i64 {
-    let content = fs::read_to_string(file).expect("Should read");
+pub struct Day07;
 
-    let mut calibration_result = 0;
-    for line in content.lines() {
-        let test_value = part1::TestValue::from_line(line);
-        let solutions = test_value.find_solutions();
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "Kenken";
+    const TIMEOUT: Option<Duration> = Some(Duration::from_secs(60));
 
-        if solutions.len() > 0 {
-            calibration_result += test_value.total;
-        }
+    type Input = Vec<Equation>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer, AocError> {
+        part_one(input).map(Answer::from)
     }
 
-    calibration_result
+    fn part_two(input: &Self::Input) -> Result<Answer, AocError> {
+        part_two(input).map(Answer::from)
+    }
 }
 
-pub fn part_two(file: &str) -> i64 {
-    let content = fs::read_to_string(file).expect("Should read");
+pub type Equation = (i64, VecDeque<i64>);
 
+pub fn parse(content: &str) -> Result<Vec<Equation>, AocError> {
     content
         .lines()
-        .par_bridge()
         .map(|line| {
-            let test_value = part2::TestValue::from_line(line);
-            let solutions = test_value.find_solutions();
+            let (total, parts): (i64, Vec<i64>) = parsing::key_values(content, line)?;
+            if parts.is_empty() {
+                return Err(AocError::parse_at(
+                    content,
+                    offset_in(content, line) + line.len(),
+                    "expected numbers after the colon",
+                ));
+            }
+            Ok((total, VecDeque::from(parts)))
+        })
+        .collect()
+}
+
+pub fn part_one(equations: &[Equation]) -> Result<i64, AocError> {
+    let deadline = deadline::current();
+    let mut calibration_result = 0;
+    for (total, parts) in equations {
+        let test_value = part1::TestValue::new(*total, parts.clone());
+        let solutions = test_value.find_solutions(deadline)?;
+
+        if !solutions.is_empty() {
+            calibration_result += test_value.total;
+        }
+    }
+
+    Ok(calibration_result)
+}
+
+pub fn part_two(equations: &[Equation]) -> Result<i64, AocError> {
+    // The deadline is per thread, so rayon's workers need it passed in
+    let deadline = deadline::current();
+    equations
+        .par_iter()
+        .map(|(total, parts)| {
+            let test_value = part2::TestValue::new(*total, parts.clone());
+            let solutions = test_value.find_solutions(deadline)?;
             if solutions.is_empty() {
-                0
+                Ok(0)
             } else {
-                test_value.total
+                Ok(test_value.total)
             }
         })
         .sum()
@@ -41,16 +86,25 @@ pub fn part_two(file: &str) -> i64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Instant;
 
     #[test]
-    fn p1() {
-        let result = part_one(&"src/day07/test.txt");
-        assert_eq!(result, 3749);
+    fn needs_numbers() {
+        let err = parse("190: 10 19\n3267:\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "parse error at line 2, column 6: expected numbers after the colon"
+        );
     }
 
     #[test]
-    fn p2() {
-        let result = part_two(&"src/day07/test.txt");
-        assert_eq!(result, 11387);
+    fn long_equations_time_out() {
+        // far too many operator combinations to try them all
+        let equation = (1, VecDeque::from(vec![2; 60]));
+        let soon = Some(Instant::now() + Duration::from_millis(50));
+        for part in [part_one, part_two] {
+            let result = deadline::with_deadline(soon, || part(std::slice::from_ref(&equation)));
+            assert!(matches!(result, Err(AocError::TimedOut)));
+        }
     }
 }
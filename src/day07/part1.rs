@@ -1,10 +1,12 @@
-use std::{collections::VecDeque, fmt};
+use crate::{deadline, error::AocError};
+use std::{collections::VecDeque, fmt, iter, time::Instant};
 
 pub struct TestValue {
     pub total: i64,
     parts: VecDeque<i64>,
 }
 
+#[derive(Clone)]
 pub enum Operation {
     Mul,
     Add,
@@ -20,47 +22,31 @@ impl fmt::Display for Operation {
 }
 
 impl TestValue {
-    pub fn from_line(line: &str) -> Self {
-        if let Some((total_raw, parts_raw)) = line.split_once(": ") {
-            let total = total_raw.trim().parse().unwrap();
-            let mut parts = VecDeque::new();
-            for part_raw in parts_raw.trim().split(" ") {
-                parts.push_back(part_raw.trim().parse().unwrap());
-            }
-
-            TestValue { total, parts }
-        } else {
-            panic!("Invalid line: {line}");
-        }
+    pub fn new(total: i64, parts: VecDeque<i64>) -> Self {
+        TestValue { total, parts }
     }
 
-    fn get_combinatoric_ops(&self) -> Vec<Vec<Operation>> {
-        let mut all_ops = Vec::new();
-        let length = self.parts.len() - 1;
-
-        if length == 0 {
-            return all_ops;
-        }
-
-        let total_combinations = 2_usize.pow(length as u32);
-
-        for i in 0..total_combinations {
-            let mut current_combination = Vec::with_capacity(length);
-
-            // For each bit position in i, determine if it should be Mul (0) or Add (1)
-            for bit_position in 0..length {
-                let operation = if (i & (1 << bit_position)) == 0 {
-                    Operation::Mul
-                } else {
-                    Operation::Add
-                };
-                current_combination.push(operation);
+    // Every combination of operations, built one at a time as they are
+    // needed since there are exponentially many
+    fn get_combinatoric_ops(&self) -> impl Iterator<Item = Vec<Operation>> {
+        let length = self.parts.len().saturating_sub(1);
+        let first = (length > 0).then(|| vec![Operation::Mul; length]);
+
+        iter::successors(first, |ops| {
+            let mut next = ops.clone();
+            // Counts up like a binary number, first operation lowest, with
+            // Mul as 0 and Add as 1
+            for op in next.iter_mut() {
+                match op {
+                    Operation::Mul => {
+                        *op = Operation::Add;
+                        return Some(next);
+                    }
+                    Operation::Add => *op = Operation::Mul,
+                }
             }
-
-            all_ops.push(current_combination);
-        }
-
-        all_ops
+            None
+        })
     }
 
     fn ops_work(&self, ops: &Vec<Operation>) -> bool {
@@ -72,7 +58,7 @@ impl TestValue {
             }
             let part = parts.pop_front().unwrap();
             match op {
-                Operation::Mul => total = total * part,
+                Operation::Mul => total *= part,
                 Operation::Add => total += part,
             };
         }
@@ -84,15 +70,16 @@ impl TestValue {
         false
     }
 
-    pub fn find_solutions(&self) -> Vec<Vec<Operation>> {
+    pub fn find_solutions(&self, deadline: Option<Instant>) -> Result<Vec<Vec<Operation>>, AocError> {
         let mut working_ops = Vec::new();
         for ops in self.get_combinatoric_ops() {
+            deadline::check_by(deadline)?;
             if self.ops_work(&ops) {
                 working_ops.push(ops);
             }
         }
 
-        working_ops
+        Ok(working_ops)
     }
 
     #[allow(dead_code)]
@@ -1,4 +1,6 @@
-use std::{collections::VecDeque, fmt};
+use crate::{deadline, error::AocError};
+use std::{collections::VecDeque, fmt, iter, time::Instant};
+use tracing::trace;
 
 pub struct TestValue {
     pub total: i64,
@@ -23,50 +25,35 @@ impl fmt::Display for Operation {
 }
 
 impl TestValue {
-    pub fn from_line(line: &str) -> Self {
-        if let Some((total_raw, parts_raw)) = line.split_once(": ") {
-            let total = total_raw.trim().parse().unwrap();
-            let mut parts = VecDeque::new();
-            for part_raw in parts_raw.trim().split(" ") {
-                parts.push_back(part_raw.trim().parse().unwrap());
-            }
-
-            TestValue { total, parts }
-        } else {
-            panic!("Invalid line: {line}");
-        }
+    pub fn new(total: i64, parts: VecDeque<i64>) -> Self {
+        TestValue { total, parts }
     }
 
-    fn get_combinatoric_ops(&self) -> Vec<Vec<Operation>> {
-        let mut all_ops = Vec::new();
-        let length = self.parts.len() - 1;
-
-        if length == 0 {
-            return all_ops;
-        }
-
-        let total_combinations = 3_usize.pow(length as u32);
-
-        for i in 0..total_combinations {
-            let mut current_combination = Vec::with_capacity(length);
-            let mut num = i;
-
-            // Convert to base 3 and map each digit to an operation
-            for _ in 0..length {
-                let operation = match num % 3 {
-                    0 => Operation::Mul,
-                    1 => Operation::Add,
-                    2 => Operation::Concat,
-                    _ => unreachable!(),
-                };
-                current_combination.push(operation);
-                num /= 3;
+    // Every combination of operations, built one at a time as they are
+    // needed since there are exponentially many
+    fn get_combinatoric_ops(&self) -> impl Iterator<Item = Vec<Operation>> {
+        let length = self.parts.len().saturating_sub(1);
+        let first = (length > 0).then(|| vec![Operation::Mul; length]);
+
+        iter::successors(first, |ops| {
+            let mut next = ops.clone();
+            // Counts up like a base 3 number, first operation lowest, with
+            // Mul as 0, Add as 1 and Concat as 2
+            for op in next.iter_mut() {
+                match op {
+                    Operation::Mul => {
+                        *op = Operation::Add;
+                        return Some(next);
+                    }
+                    Operation::Add => {
+                        *op = Operation::Concat;
+                        return Some(next);
+                    }
+                    Operation::Concat => *op = Operation::Mul,
+                }
             }
-
-            all_ops.push(current_combination);
-        }
-
-        all_ops
+            None
+        })
     }
 
     fn ops_work(&self, ops: &Vec<Operation>) -> bool {
@@ -82,9 +69,10 @@ impl TestValue {
                 Operation::Mul => total *= part,
                 Operation::Add => total += part,
                 Operation::Concat => {
-                    let concatenated = format!("{}{}", total, part)
-                        .parse::<i64>()
-                        .expect("Failed to parse concatenated number");
+                    // Too big for an i64 means far too big for the total
+                    let Ok(concatenated) = format!("{}{}", total, part).parse::<i64>() else {
+                        return false;
+                    };
                     total = concatenated;
                 }
             };
@@ -97,16 +85,17 @@ impl TestValue {
         false
     }
 
-    pub fn find_solutions(&self) -> Vec<Vec<Operation>> {
+    pub fn find_solutions(&self, deadline: Option<Instant>) -> Result<Vec<Vec<Operation>>, AocError> {
         let mut working_ops = Vec::new();
         for ops in self.get_combinatoric_ops() {
-            //println!("{:?}", ops);
+            deadline::check_by(deadline)?;
+            trace!("{:?}", ops);
             if self.ops_work(&ops) {
                 working_ops.push(ops);
             }
         }
 
-        working_ops
+        Ok(working_ops)
     }
 
     #[allow(dead_code)]
@@ -1,13 +1,44 @@
-use std::{collections::HashSet, fs};
+use crate::{
+    error::AocError,
+    geometry::Vec2,
+    parsing,
+    solution::{Answer, Solution},
+};
+use std::collections::HashSet;
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+    const TITLE: &'static str = "Resonant collinearity";
+
+    type Input = City;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part_one(input).into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part_two(input).into())
+    }
+}
+
+pub struct City {
+    antennae: Vec<Antenna>,
+    size: Vec2,
+}
 
 #[derive(Debug, Eq, PartialEq, Hash)]
 struct Antenna {
-    symbol: String,
-    x: i32,
-    y: i32,
+    symbol: char,
+    pos: Vec2,
 }
 
-fn get_pairs(antennae: &Vec<Antenna>) -> Vec<(&Antenna, &Antenna)> {
+fn get_pairs(antennae: &[Antenna]) -> Vec<(&Antenna, &Antenna)> {
     let mut pairs = Vec::new();
     for (i, antenna) in antennae.iter().enumerate() {
         for other_antenna in &antennae[i + 1..] {
@@ -17,49 +48,39 @@ fn get_pairs(antennae: &Vec<Antenna>) -> Vec<(&Antenna, &Antenna)> {
         }
     }
 
-    return pairs;
+    pairs
 }
 
-pub fn part_one(file: &str) -> usize {
-    let content = fs::read_to_string(file).expect("Should read");
-
-    // calculate bounds
-    let lines: Vec<&str> = content.lines().collect();
-    let y_size = lines.len() as i32;
-    let x_size = lines[0].trim().chars().count() as i32;
+pub fn parse(content: &str) -> Result<City, AocError> {
+    let grid = parsing::char_grid(content, content)?;
+    if grid.height() == 0 {
+        return Err(AocError::parse_at(content, 0, "expected at least one row"));
+    }
 
     // Gather all antennae
-    let mut antennae = Vec::new();
-
-    for (y, line) in content.lines().enumerate() {
-        for (x, symbol) in line.trim().chars().enumerate() {
-            if symbol != '.' {
-                antennae.push(Antenna {
-                    symbol: symbol.to_string(),
-                    x: x as i32,
-                    y: y as i32,
-                });
-            }
-        }
-    }
+    let antennae = grid
+        .iter()
+        .filter(|(_, symbol)| **symbol != '.')
+        .map(|(pos, &symbol)| Antenna {
+            symbol,
+            pos: Vec2::from(pos),
+        })
+        .collect();
+
+    Ok(City {
+        antennae,
+        size: Vec2::from((grid.width(), grid.height())),
+    })
+}
 
+pub fn part_one(city: &City) -> usize {
     // Find antinodes for each pair
     let mut antinodes = HashSet::new();
-    for (a, b) in get_pairs(&antennae) {
-        let mut new_nodes = Vec::new();
-        new_nodes.push(Antenna {
-            symbol: "#".to_string(),
-            x: 2 * b.x - a.x,
-            y: 2 * b.y - a.y,
-        });
-        new_nodes.push(Antenna {
-            symbol: "#".to_string(),
-            x: 2 * a.x - b.x,
-            y: 2 * a.y - b.y,
-        });
+    for (a, b) in get_pairs(&city.antennae) {
+        let new_nodes = [b.pos * 2 - a.pos, a.pos * 2 - b.pos];
 
         for node in new_nodes {
-            if node.x >= 0 && node.x < x_size && node.y >= 0 && node.y < y_size {
+            if node.within(city.size) {
                 antinodes.insert(node);
             }
         }
@@ -68,95 +89,31 @@ pub fn part_one(file: &str) -> usize {
     antinodes.len()
 }
 
-pub fn part_two(file: &str) -> usize {
-    let content = fs::read_to_string(file).expect("Should read");
-
-    // calculate bounds
-    let lines: Vec<&str> = content.lines().collect();
-    let y_size = lines.len() as i32;
-    let x_size = lines[0].trim().chars().count() as i32;
-
-    // Gather all antennae
-    let mut antennae = Vec::new();
-
-    for (y, line) in content.lines().enumerate() {
-        for (x, symbol) in line.trim().chars().enumerate() {
-            if symbol != '.' {
-                antennae.push(Antenna {
-                    symbol: symbol.to_string(),
-                    x: x as i32,
-                    y: y as i32,
-                });
-            }
-        }
-    }
-
+pub fn part_two(city: &City) -> usize {
     // Find antinodes for each pair
     let mut antinodes = HashSet::new();
-    for (a, b) in get_pairs(&antennae) {
+    for (a, b) in get_pairs(&city.antennae) {
         // Each node is it's pair's antinode
-        antinodes.insert(Antenna {
-            symbol: "#".to_string(),
-            x: a.x,
-            y: a.y,
-        });
-        antinodes.insert(Antenna {
-            symbol: "#".to_string(),
-            x: b.x,
-            y: b.y,
-        });
+        antinodes.insert(a.pos);
+        antinodes.insert(b.pos);
         // Calculate the "step" vector between points
-        let dx = b.x - a.x;
-        let dy = b.y - a.y;
+        let step = b.pos - a.pos;
 
         // Start from each antenna and keep stepping outward until we hit bounds
         // Direction 1: Starting from b, stepping away from a
-        let mut x = b.x;
-        let mut y = b.y;
-        while x >= 0 && x < x_size && y >= 0 && y < y_size {
-            x += dx;
-            y += dy;
-            if x >= 0 && x < x_size && y >= 0 && y < y_size {
-                antinodes.insert(Antenna {
-                    symbol: "#".to_string(),
-                    x,
-                    y,
-                });
-            }
+        let mut node = b.pos + step;
+        while node.within(city.size) {
+            antinodes.insert(node);
+            node += step;
         }
 
         // Direction 2: Starting from a, stepping away from b
-        let mut x = a.x;
-        let mut y = a.y;
-        while x >= 0 && x < x_size && y >= 0 && y < y_size {
-            x -= dx;
-            y -= dy;
-            if x >= 0 && x < x_size && y >= 0 && y < y_size {
-                antinodes.insert(Antenna {
-                    symbol: "#".to_string(),
-                    x,
-                    y,
-                });
-            }
+        let mut node = a.pos - step;
+        while node.within(city.size) {
+            antinodes.insert(node);
+            node -= step;
         }
     }
 
     antinodes.len()
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn p1() {
-        let result = part_one(&"src/day08/test.txt");
-        assert_eq!(result, 14);
-    }
-
-    #[test]
-    fn p2() {
-        let result = part_two(&"src/day08/test.txt");
-        assert_eq!(result, 34);
-    }
-}
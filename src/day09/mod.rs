@@ -1,10 +1,43 @@
-use std::fs;
+use crate::{
+    error::{offset_in, AocError},
+    solution::{Answer, Solution},
+};
+use tracing::{debug, trace};
 
-fn load_blocks(content: &str) -> Vec<String> {
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+    const TITLE: &'static str = "Defragmentation";
+
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part_one(input).into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part_two(input).into())
+    }
+}
+
+fn load_blocks(content: &str) -> Result<Vec<String>, AocError> {
     let mut blocks = Vec::new();
     let mut id = 0;
-    for (i, c) in content.trim().chars().enumerate() {
-        let digit = c.to_digit(10).unwrap();
+    let disk_map = content.trim();
+    let start = offset_in(content, disk_map);
+    for (i, c) in disk_map.chars().enumerate() {
+        let Some(digit) = c.to_digit(10) else {
+            return Err(AocError::parse_at(
+                content,
+                start + i,
+                format!("expected a digit, found `{c}`"),
+            ));
+        };
         if (i % 2) == 0 {
             for _ in 0..digit {
                 blocks.push(id.to_string())
@@ -17,15 +50,15 @@ fn load_blocks(content: &str) -> Vec<String> {
         }
     }
 
-    blocks
+    Ok(blocks)
 }
 
 fn get_last_block(blocks: &mut Vec<String>) -> Option<String> {
     if let Some(last) = blocks.pop() {
         if last == "." {
-            return get_last_block(blocks);
+            get_last_block(blocks)
         } else {
-            return Some(last);
+            Some(last)
         }
     } else {
         None
@@ -47,23 +80,23 @@ fn get_last_id_block(blocks: &mut Vec<String>) -> Option<(usize, Vec<String>)> {
     }
 }
 
-fn index_of_free_block(blocks: &Vec<String>, length: usize) -> Option<usize> {
+fn index_of_free_block(blocks: &[String], length: usize) -> Option<usize> {
     let mut index = 0;
-    while index <= blocks.len() {
+    while index < blocks.len() {
         let mut run_length = 0;
-        let mut run_index = index.clone();
-        while blocks[run_index] == "." {
+        let mut run_index = index;
+        while blocks.get(run_index).is_some_and(|block| block == ".") {
             run_index += 1;
             run_length += 1;
-            //println!(
-            //    "run_index: {:?}, blocks len: {:?}, run_length: {:?}/{:?}",
-            //    run_index,
-            //    blocks.len(),
-            //    run_length,
-            //    length
-            //);
+            trace!(
+                "run_index: {:?}, blocks len: {:?}, run_length: {:?}/{:?}",
+                run_index,
+                blocks.len(),
+                run_length,
+                length
+            );
             if run_index + 1 >= blocks.len() {
-                //println!("running off end");
+                trace!("running off end");
                 return None;
             }
         }
@@ -72,13 +105,13 @@ fn index_of_free_block(blocks: &Vec<String>, length: usize) -> Option<usize> {
         }
         run_index += 1;
         index = run_index;
-        //println!("index: {:?}", index);
+        trace!("index: {:?}", index);
     }
 
     None
 }
 
-fn checksum(blocks: &Vec<String>) -> i64 {
+fn checksum(blocks: &[String]) -> i64 {
     let mut total = 0;
     for (pos, id) in blocks.iter().enumerate() {
         if id == "." {
@@ -89,94 +122,72 @@ fn checksum(blocks: &Vec<String>) -> i64 {
     total
 }
 
-pub fn part_one(file: &str) -> i64 {
-    let content = fs::read_to_string(file).expect("File should exist and be readable to string");
+pub fn parse(content: &str) -> Result<Vec<String>, AocError> {
+    load_blocks(content)
+}
 
-    //println!("{:?}", blocks.join(""));
-    let mut blocks = load_blocks(&content);
+pub fn part_one(blocks: &[String]) -> i64 {
+    let mut blocks = blocks.to_vec();
+    debug!("{:?}", blocks.join(""));
 
     while blocks.contains(&".".to_string()) {
         let last_block = get_last_block(&mut blocks);
         let first_blank_pos = blocks.iter().position(|c| c == ".");
-        match last_block {
-            Some(last) => match first_blank_pos {
+        if let Some(last) = last_block {
+            match first_blank_pos {
                 Some(pos) => blocks[pos] = last,
                 None => blocks.push(last), // put it back
-            },
-            None => {}
+            }
         }
     }
 
-    //println!("{:?}", blocks.join(""));
+    debug!("{:?}", blocks.join(""));
 
     checksum(&blocks)
 }
 
-pub fn part_two(file: &str) -> i64 {
-    let content = fs::read_to_string(file).expect("File should exist and be readable to string");
-
-    let mut blocks = load_blocks(&content);
-    //println!("{:?}", blocks.join(""));
+pub fn part_two(blocks: &[String]) -> i64 {
+    let mut blocks = blocks.to_vec();
+    debug!("{:?}", blocks.join(""));
 
     let mut completed_ids = Vec::new();
     let mut cloned_blocks = blocks.clone();
-    loop {
-        if let Some((start, last_id_block)) = get_last_id_block(&mut cloned_blocks) {
-            if completed_ids.contains(&last_id_block[0].clone()) {
-                continue;
-            }
-            match index_of_free_block(&blocks, last_id_block.len()) {
-                Some(insert_at) => {
-                    if insert_at > start {
-                        //println!(
-                        //    "{:?} would move in the wrong direction, keep it",
-                        //    last_id_block
-                        //);
-                        continue;
-                    }
-                    //println!(
-                    //    "move {:?} from {:?} to {:?}",
-                    //    last_id_block, start, insert_at
-                    //);
-                    blocks.splice(
-                        insert_at..insert_at + last_id_block.len(),
-                        last_id_block.iter().cloned(),
-                    );
-                    blocks.splice(
-                        start..start + last_id_block.len(),
-                        vec![".".to_string(); last_id_block.len()],
+    while let Some((start, last_id_block)) = get_last_id_block(&mut cloned_blocks) {
+        if completed_ids.contains(&last_id_block[0].clone()) {
+            continue;
+        }
+        match index_of_free_block(&blocks, last_id_block.len()) {
+            Some(insert_at) => {
+                if insert_at > start {
+                    debug!(
+                        "{:?} would move in the wrong direction, keep it",
+                        last_id_block
                     );
+                    continue;
                 }
-                None => {
-                    //println!("No space, keep {:?} where it was", last_id_block);
-                    blocks.splice(start..start + last_id_block.len(), last_id_block.clone());
-                }
+                debug!(
+                    "move {:?} from {:?} to {:?}",
+                    last_id_block, start, insert_at
+                );
+                blocks.splice(
+                    insert_at..insert_at + last_id_block.len(),
+                    last_id_block.iter().cloned(),
+                );
+                blocks.splice(
+                    start..start + last_id_block.len(),
+                    vec![".".to_string(); last_id_block.len()],
+                );
+            }
+            None => {
+                debug!("No space, keep {:?} where it was", last_id_block);
+                blocks.splice(start..start + last_id_block.len(), last_id_block.clone());
             }
-            completed_ids.push(last_id_block[0].clone());
-        } else {
-            break;
         }
-        //println!("{:?}", blocks.join(""));
+        completed_ids.push(last_id_block[0].clone());
+        debug!("{:?}", blocks.join(""));
     }
 
-    //println!("{:?}", blocks.join(""));
+    debug!("{:?}", blocks.join(""));
 
     checksum(&blocks)
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn p1() {
-        let result = part_one(&"src/day09/test.txt");
-        assert_eq!(result, 1928);
-    }
-
-    #[test]
-    fn p2() {
-        let result = part_two(&"src/day09/test.txt");
-        assert_eq!(result, 2858);
-    }
-}
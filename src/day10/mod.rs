@@ -1,168 +1,83 @@
-use std::{collections::HashSet, fs};
+use crate::{
+    error::AocError,
+    grid::{Grid, Pos},
+    parsing,
+    search::{self, Paths},
+    solution::{Answer, Solution},
+};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct Point {
-    x: usize,
-    y: usize,
-    value: u32,
-}
-
-struct Map {
-    grid: Vec<Vec<Point>>,
-}
-
-impl Map {
-    fn new(content: &str) -> Self {
-        let mut grid = Vec::new();
-
-        for (y, line) in content.lines().enumerate() {
-            let mut row = Vec::new();
-            for (x, c) in line.chars().enumerate() {
-                row.push(Point {
-                    x,
-                    y,
-                    value: c.to_digit(10).unwrap(),
-                });
-            }
-            grid.push(row);
-        }
+pub struct Day10;
 
-        Map { grid }
-    }
-
-    fn width(&self) -> usize {
-        self.grid[0].len()
-    }
-    fn height(&self) -> usize {
-        self.grid.len()
-    }
-}
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+    const TITLE: &'static str = "Topology search";
 
-fn trailhead_score<'a>(map: &'a Map, start: &'a Point, reached: &mut HashSet<&'a Point>) -> i64 {
-    let mut score = 0;
+    type Input = Map;
 
-    if start.x > 0 {
-        let left = &map.grid[start.y][start.x - 1];
-        if left.value == start.value + 1 {
-            score += trailhead_score(&map, &left, reached);
-        }
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
     }
 
-    if start.x < map.width() - 1 {
-        let right = &map.grid[start.y][start.x + 1];
-        if right.value == start.value + 1 {
-            score += trailhead_score(&map, &right, reached);
-        }
+    fn part_one(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part_one(input).into())
     }
 
-    if start.y > 0 {
-        let up = &map.grid[start.y - 1][start.x];
-        if up.value == start.value + 1 {
-            score += trailhead_score(&map, &up, reached);
-        }
+    fn part_two(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part_two(input).into())
     }
-
-    if start.y < map.height() - 1 {
-        let down = &map.grid[start.y + 1][start.x];
-        if down.value == start.value + 1 {
-            score += trailhead_score(&map, &down, reached);
-        }
-    }
-
-    if start.value == 9 && !reached.contains(start) {
-        reached.insert(start);
-        score += 1;
-    }
-
-    score
 }
 
-fn trailhead_rating(map: &Map, start: &Point) -> i64 {
-    let mut score = 0;
-
-    if start.x > 0 {
-        let left = &map.grid[start.y][start.x - 1];
-        if left.value == start.value + 1 {
-            score += trailhead_rating(&map, &left);
-        }
-    }
-
-    if start.x < map.width() - 1 {
-        let right = &map.grid[start.y][start.x + 1];
-        if right.value == start.value + 1 {
-            score += trailhead_rating(&map, &right);
-        }
-    }
-
-    if start.y > 0 {
-        let up = &map.grid[start.y - 1][start.x];
-        if up.value == start.value + 1 {
-            score += trailhead_rating(&map, &up);
-        }
-    }
-
-    if start.y < map.height() - 1 {
-        let down = &map.grid[start.y + 1][start.x];
-        if down.value == start.value + 1 {
-            score += trailhead_rating(&map, &down);
-        }
-    }
-
-    if start.value == 9 {
-        score += 1;
-    }
-
-    score
+pub struct Map {
+    grid: Grid<u32>,
 }
 
-pub fn part_one(file: &str) -> i64 {
-    let content = fs::read_to_string(file).expect("File should exist and be readable to string");
-    let map = Map::new(&content);
-
-    let mut score = 0;
-
-    for row in map.grid.iter() {
-        for point in row {
-            if point.value == 0 {
-                let mut reached = HashSet::new();
-                score += trailhead_score(&map, &point, &mut reached);
-            }
-        }
-    }
-
-    score
+// Every step climbs exactly one, so every trail to a summit is a shortest
+// path and the search's path counts are trail counts
+fn trails_from(map: &Map, start: Pos) -> Paths<Pos> {
+    search::bfs(start, |&pos| {
+        map.grid
+            .neighbors4(pos)
+            .filter(move |&next| map.grid[next] == map.grid[pos] + 1)
+    })
 }
 
-pub fn part_two(file: &str) -> i64 {
-    let content = fs::read_to_string(file).expect("File should exist and be readable to string");
-    let map = Map::new(&content);
-
-    let mut score = 0;
-
-    for row in map.grid.iter() {
-        for point in row {
-            if point.value == 0 {
-                score += trailhead_rating(&map, &point);
-            }
-        }
-    }
+fn summits(map: &Map, trails: &Paths<Pos>) -> Vec<Pos> {
+    trails
+        .reached()
+        .filter(|&&pos| map.grid[pos] == 9)
+        .copied()
+        .collect()
+}
 
-    score
+pub fn parse(content: &str) -> Result<Map, AocError> {
+    let grid = parsing::grid(content, content, |c| {
+        c.to_digit(10)
+            .ok_or_else(|| format!("expected a height, found `{c}`"))
+    })?;
+    Ok(Map { grid })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn trailheads(map: &Map) -> impl Iterator<Item = Pos> + '_ {
+    map.grid
+        .iter()
+        .filter(|(_, value)| **value == 0)
+        .map(|(pos, _)| pos)
+}
 
-    #[test]
-    fn p1() {
-        let result = part_one(&"src/day10/test.txt");
-        assert_eq!(result, 36);
-    }
+pub fn part_one(map: &Map) -> i64 {
+    trailheads(map)
+        .map(|start| summits(map, &trails_from(map, start)).len() as i64)
+        .sum()
+}
 
-    #[test]
-    fn p2() {
-        let result = part_two(&"src/day10/test.txt");
-        assert_eq!(result, 81);
-    }
+pub fn part_two(map: &Map) -> i64 {
+    trailheads(map)
+        .map(|start| {
+            let trails = trails_from(map, start);
+            summits(map, &trails)
+                .iter()
+                .map(|summit| trails.count_paths(summit) as i64)
+                .sum::<i64>()
+        })
+        .sum()
 }
@@ -1,20 +1,41 @@
-use std::{collections::HashMap, fs};
+use crate::{
+    error::AocError,
+    parsing,
+    params::{self, Param},
+    solution::{Answer, Solution},
+};
+use std::collections::HashMap;
 
-fn get_stones(content: &str) -> Vec<usize> {
-    let mut stones = Vec::new();
+pub struct Day11;
 
-    for stone_raw in content.trim().split(" ") {
-        stones.push(stone_raw.parse::<usize>().unwrap());
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+    const TITLE: &'static str = "Stones with numbers on 'em";
+    const PARAMS: &'static [Param] = &[Param {
+        name: "blinks",
+        about: "times the stones blink, 25 in part one and 75 in part two",
+    }];
+
+    type Input = Vec<usize>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part_one(input).into())
     }
 
-    stones
+    fn part_two(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part_two(input).into())
+    }
 }
 
 fn apply_rules(stone: &usize) -> Vec<usize> {
     let stone_str = stone.to_string();
     if *stone == 0 {
         vec![1]
-    } else if stone_str.len() % 2 == 0 {
+    } else if stone_str.len().is_multiple_of(2) {
         let left = stone_str[..stone_str.len() / 2].parse::<usize>().unwrap();
         let right = stone_str[(stone_str.len() / 2)..].parse::<usize>().unwrap();
         vec![left, right]
@@ -23,13 +44,15 @@ fn apply_rules(stone: &usize) -> Vec<usize> {
     }
 }
 
-pub fn part_one(file: &str) -> usize {
-    let content = fs::read_to_string(file).expect("File should exist and be readable to string");
+pub fn parse(content: &str) -> Result<Vec<usize>, AocError> {
+    parsing::words(content, content)
+}
 
-    let mut stones = get_stones(&content);
+pub fn part_one(stones: &[usize]) -> usize {
+    let mut stones = stones.to_vec();
     let mut new_stones = Vec::new();
 
-    for _ in 0..25 {
+    for _ in 0..params::get("blinks", 25) {
         for stone in &stones {
             new_stones.extend(apply_rules(stone));
         }
@@ -40,15 +63,13 @@ pub fn part_one(file: &str) -> usize {
     stones.len()
 }
 
-pub fn part_two(file: &str) -> usize {
-    let content = fs::read_to_string(file).expect("File should exist and be readable to string");
-
-    let mut stones = get_stones(&content)
-        .into_iter()
-        .map(|value| (value, 1))
+pub fn part_two(stones: &[usize]) -> usize {
+    let mut stones = stones
+        .iter()
+        .map(|value| (*value, 1))
         .collect::<HashMap<usize, usize>>();
 
-    for _ in 0..75 {
+    for _ in 0..params::get("blinks", 75) {
         let mut new_stones = HashMap::new();
         for (stone, old_count) in stones {
             for new_stone in apply_rules(&stone) {
@@ -67,12 +88,10 @@ mod tests {
     use super::*;
 
     #[test]
-    fn p1() {
+    fn rules() {
         assert_eq!(apply_rules(&0), [1]);
         assert_eq!(apply_rules(&1250), [12, 50]);
         assert_eq!(apply_rules(&1000), [10, 0]);
         assert_eq!(apply_rules(&125), [253000]);
-        let result = part_one(&"src/day11/test.txt");
-        assert_eq!(result, 55312);
     }
 }
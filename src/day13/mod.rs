@@ -1,21 +1,65 @@
-use num::{rational::Ratio, BigInt, BigRational, FromPrimitive, ToPrimitive};
+use crate::{
+    error::AocError,
+    params::{self, Param},
+    parsing::{self, Record},
+    solution::{Answer, Solution},
+};
+use num::{rational::Ratio, BigInt, BigRational, ToPrimitive, Zero};
 use regex::Regex;
-use std::fs;
 
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+    const TITLE: &'static str = "Claw machine system of equations";
+    const PARAMS: &'static [Param] = &[Param {
+        name: "offset",
+        about: "added to both prize coordinates in part two",
+    }];
+
+    type Input = Vec<Machine>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer, AocError> {
+        part_one(input).map(Answer::from)
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Answer, AocError> {
+        part_two(input).map(Answer::from)
+    }
+}
+
+#[derive(Clone)]
 struct Coord {
     x: Ratio<BigInt>,
     y: Ratio<BigInt>,
 }
 
-struct Machine {
+#[derive(Clone)]
+pub struct Machine {
     a: Coord,
     b: Coord,
     prize: Coord,
 }
 
 impl Machine {
+    pub fn with_prize_offset(&self, offset: i64) -> Machine {
+        let offset = BigRational::from_integer(offset.into());
+        Machine {
+            prize: Coord {
+                x: &self.prize.x + &offset,
+                y: &self.prize.y + &offset,
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Tokens needed to win the prize, or `None` if it can't be won.
     // rust is clunky for math?
-    fn solution(&self) -> Option<i64> {
+    pub fn solution(&self) -> Result<Option<i64>, AocError> {
         // A*a.x + B*b.x = prize.x
         // A*a.y + B*b.y = prize.y
         //
@@ -28,91 +72,82 @@ impl Machine {
         // B = (prize.x/a.x - prize.y/a.y)/(b.x/a.x - b.y/a.y)
         // A = (prize.x - B*b.x) / a.x
 
-        let b_presses = ((&self.prize.x / &self.a.x) - (&self.prize.y / &self.a.y))
-            / ((&self.b.x / &self.a.x) - (&self.b.y / &self.a.y));
+        if self.a.x.is_zero() || self.a.y.is_zero() {
+            return Err(AocError::invalid("Button A must move along both axes"));
+        }
+        let slope = (&self.b.x / &self.a.x) - (&self.b.y / &self.a.y);
+        if slope.is_zero() {
+            return Err(AocError::invalid("Buttons A and B move in the same direction"));
+        }
+
+        let b_presses = ((&self.prize.x / &self.a.x) - (&self.prize.y / &self.a.y)) / slope;
         let a_presses = (&self.prize.x - (&b_presses * &self.b.x)) / &self.a.x;
 
         if !b_presses.is_integer() || !a_presses.is_integer() {
-            return None;
+            return Ok(None);
         }
 
-        let a_tokens = a_presses.to_i64().unwrap() * 3;
-        let b_tokens = b_presses.to_i64().unwrap() * 1;
+        let (Some(a_presses), Some(b_presses)) = (a_presses.to_i64(), b_presses.to_i64()) else {
+            return Err(AocError::invalid("Too many button presses to count"));
+        };
 
-        Some(a_tokens + b_tokens)
+        Ok(Some(a_presses * 3 + b_presses))
     }
 }
 
-fn get_machines(content: &str, prize_mod: i64) -> Vec<Machine> {
-    let pattern = r"(?:Button A: X\+(?P<ax>\d+), Y\+(?P<ay>\d+)\nButton B: X\+(?P<bx>\d+), Y\+(?P<by>\d+)\nPrize: X=(?P<px>\d+), Y=(?P<py>\d+))";
+fn number(record: &Record, name: &str) -> Result<BigRational, AocError> {
+    Ok(BigRational::from_integer(record.get(name)?))
+}
+
+fn get_machines(content: &str) -> Result<Vec<Machine>, AocError> {
+    let pattern = r"^Button A: X\+(?P<ax>\d+), Y\+(?P<ay>\d+)\r?\nButton B: X\+(?P<bx>\d+), Y\+(?P<by>\d+)\r?\nPrize: X=(?P<px>\d+), Y=(?P<py>\d+)$";
     let re = Regex::new(pattern).unwrap();
 
     let mut machines = Vec::new();
-    for caps in re.captures_iter(content) {
+    for section in parsing::sections(content) {
+        let record = parsing::record(content, section, &re, "two buttons and a prize")?;
         machines.push(Machine {
             a: Coord {
-                x: BigRational::from_i64(caps["ax"].parse::<i64>().unwrap()).unwrap(),
-                y: BigRational::from_i64(caps["ay"].parse::<i64>().unwrap()).unwrap(),
+                x: number(&record, "ax")?,
+                y: number(&record, "ay")?,
             },
             b: Coord {
-                x: BigRational::from_i64(caps["bx"].parse::<i64>().unwrap()).unwrap(),
-                y: BigRational::from_i64(caps["by"].parse::<i64>().unwrap()).unwrap(),
+                x: number(&record, "bx")?,
+                y: number(&record, "by")?,
             },
             prize: Coord {
-                x: BigRational::from_i64(caps["px"].parse::<i64>().unwrap() + prize_mod).unwrap(),
-                y: BigRational::from_i64(caps["py"].parse::<i64>().unwrap() + prize_mod).unwrap(),
+                x: number(&record, "px")?,
+                y: number(&record, "py")?,
             },
         });
     }
 
-    machines
+    Ok(machines)
 }
 
-pub fn part_one(file: &str) -> i64 {
-    let content = fs::read_to_string(file).expect("File should exist and be readable");
-
-    let machines = get_machines(&content, 0);
+pub fn parse(content: &str) -> Result<Vec<Machine>, AocError> {
+    get_machines(content)
+}
 
+pub fn part_one(machines: &[Machine]) -> Result<i64, AocError> {
     let mut total = 0;
     for machine in machines {
-        match machine.solution() {
-            Some(tokens) => total += tokens,
-            None => {}
+        if let Some(tokens) = machine.solution()? {
+            total += tokens;
         }
     }
 
-    total
+    Ok(total)
 }
 
-pub fn part_two(file: &str) -> i64 {
-    let content = fs::read_to_string(file).expect("File should exist and be readable");
-
-    let machines = get_machines(&content, 10000000000000);
-
+pub fn part_two(machines: &[Machine]) -> Result<i64, AocError> {
     let mut total = 0;
+    let offset = params::get("offset", 10000000000000);
     for machine in machines {
-        match machine.solution() {
-            Some(tokens) => total += tokens,
-            None => {}
+        if let Some(tokens) = machine.with_prize_offset(offset).solution()? {
+            total += tokens;
         }
     }
 
-    total
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn p1() {
-        let result = part_one(&"src/day13/test.txt");
-        assert_eq!(result, 480);
-    }
-
-    #[test]
-    fn p2() {
-        let result = part_two(&"src/day13/test.txt");
-        assert_eq!(result, 875318608908);
-    }
+    Ok(total)
 }
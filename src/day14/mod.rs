@@ -1,94 +1,93 @@
+use crate::{
+    animate,
+    error::AocError,
+    geometry::Vec2,
+    grid::{Grid, Pos},
+    params::{self, Param},
+    parsing,
+    render::{Cell, Layer, Render, Rgb},
+    solution::{Answer, Solution},
+};
 use regex::Regex;
-use std::fs;
-use std::fs::File;
-use std::io::Write;
 
-#[derive(Debug)]
-struct Coord {
-    x: i32,
-    y: i32,
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+    const TITLE: &'static str = "Grid trajectories";
+    const PARAMS: &'static [Param] = &[Param {
+        name: "seconds",
+        about: "how long the robots move for in part one",
+    }];
+
+    type Input = (Vec<Robot>, Vec2);
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part_one(input).into())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Answer, AocError> {
+        part_two(input).map(Answer::from)
+    }
 }
 
 #[derive(Debug)]
-struct Robot {
-    p: Coord,
-    v: Coord,
+pub struct Robot {
+    p: Vec2,
+    v: Vec2,
 }
 
-fn get_robots(content: &str) -> (Vec<Robot>, Coord) {
-    let pattern = Regex::new(r"p=(?P<px>\d+),(?P<py>\d+)\s+v=(?P<vx>-?\d+),(?P<vy>-?\d+)").unwrap();
+fn get_robots(content: &str) -> Result<(Vec<Robot>, Vec2), AocError> {
+    let pattern =
+        Regex::new(r"^p=(?P<px>\d+),(?P<py>\d+)\s+v=(?P<vx>-?\d+),(?P<vy>-?\d+)$").unwrap();
 
     let mut robots = Vec::new();
     let mut max_x = 0;
     let mut max_y = 0;
     for line in content.lines() {
-        if let Some(caps) = pattern.captures(line) {
-            let px: i32 = caps.name("px").unwrap().as_str().parse().unwrap();
-            let py: i32 = caps.name("py").unwrap().as_str().parse().unwrap();
-            let vx: i32 = caps.name("vx").unwrap().as_str().parse().unwrap();
-            let vy: i32 = caps.name("vy").unwrap().as_str().parse().unwrap();
-
-            robots.push(Robot {
-                p: Coord { x: px, y: py },
-                v: Coord { x: vx, y: vy },
-            });
-
-            if px > max_x {
-                max_x = px;
-            }
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let record = parsing::record(content, line, &pattern, "a robot like `p=0,4 v=3,-3`")?;
+        let px: i64 = record.get("px")?;
+        let py: i64 = record.get("py")?;
+        let vx: i64 = record.get("vx")?;
+        let vy: i64 = record.get("vy")?;
+
+        robots.push(Robot {
+            p: Vec2::new(px, py),
+            v: Vec2::new(vx, vy),
+        });
+
+        if px > max_x {
+            max_x = px;
+        }
 
-            if py > max_y {
-                max_y = py;
-            }
+        if py > max_y {
+            max_y = py;
         }
     }
 
-    let dimensions = Coord {
-        x: max_x + 1,
-        y: max_y + 1,
-    };
+    let dimensions = Vec2::new(max_x + 1, max_y + 1);
 
-    (robots, dimensions)
+    Ok((robots, dimensions))
 }
 
-fn safety_factor(robots: &Vec<Robot>, dimensions: &Coord, seconds: i32) -> (Vec<Vec<i32>>, i32) {
+fn safety_factor(robots: &[Robot], dimensions: &Vec2, seconds: i64) -> (Grid<i32>, i32) {
     let mut quadrants = vec![0, 0, 0, 0];
 
-    let mut grid = Vec::new();
-
-    for _ in 0..dimensions.y {
-        let mut row = Vec::new();
-        for _ in 0..dimensions.x {
-            row.push(0);
-        }
-        grid.push(row);
-    }
+    let mut grid = Grid::new(dimensions.x as usize, dimensions.y as usize, 0);
 
     for robot in robots {
-        let x_res = (robot.v.x * seconds) % dimensions.x;
-        let y_res = (robot.v.y * seconds) % dimensions.y;
-
-        let x_disp = robot.p.x + x_res;
-        let y_disp = robot.p.y + y_res;
-
-        let x_final;
-        let y_final;
-
-        if x_disp >= dimensions.x {
-            x_final = x_disp % dimensions.x;
-        } else if x_disp < 0 {
-            x_final = dimensions.x + x_disp;
-        } else {
-            x_final = x_disp;
-        }
-
-        if y_disp >= dimensions.y {
-            y_final = y_disp % dimensions.y;
-        } else if y_disp < 0 {
-            y_final = dimensions.y + y_disp;
-        } else {
-            y_final = y_disp;
-        }
+        let Vec2 {
+            x: x_final,
+            y: y_final,
+        } = (robot.p + robot.v * seconds).wrap(*dimensions);
 
         if x_final > (dimensions.x / 2) {
             if y_final > (dimensions.y / 2) {
@@ -108,7 +107,7 @@ fn safety_factor(robots: &Vec<Robot>, dimensions: &Coord, seconds: i32) -> (Vec<
             }
         }
 
-        grid[y_final as usize][x_final as usize] += 1;
+        grid[(x_final as usize, y_final as usize)] += 1;
     }
 
     let mut safety_factor = 0;
@@ -123,76 +122,57 @@ fn safety_factor(robots: &Vec<Robot>, dimensions: &Coord, seconds: i32) -> (Vec<
     (grid, safety_factor)
 }
 
-pub fn part_one(file: &str) -> i32 {
-    let content = fs::read_to_string(file).expect("Should be readable");
-
-    let (robots, dimensions) = get_robots(&content);
+pub fn parse(content: &str) -> Result<(Vec<Robot>, Vec2), AocError> {
+    get_robots(content)
+}
 
-    let (_, sf) = safety_factor(&robots, &dimensions, 100);
+pub fn part_one((robots, dimensions): &(Vec<Robot>, Vec2)) -> i32 {
+    let seconds = params::get("seconds", 100);
+    // only worked out second by second when the steps are being recorded
+    for t in 0..=seconds {
+        animate::capture(|| Floor::new(safety_factor(robots, dimensions, t).0));
+    }
+    let (_, sf) = safety_factor(robots, dimensions, seconds);
 
     sf
 }
 
-fn save_grid_as_svg(grid: &Vec<Vec<i32>>, filename: &str, cell_size: u32) {
-    let width = grid[0].len() as u32 * cell_size;
-    let height = grid.len() as u32 * cell_size;
-
-    // First, find the maximum value in the grid to scale colors properly
-    let max_value = grid
-        .iter()
-        .flat_map(|row| row.iter())
-        .max()
-        .copied()
-        .unwrap_or(1);
-
-    let mut svg = String::from(format!(
-        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" style="background-color: white">"#,
-        width, height
-    ));
-
-    for (y, row) in grid.iter().enumerate() {
-        for (x, &value) in row.iter().enumerate() {
-            // Scale the value to 0-255 range based on the maximum value
-            let color_value = if value <= 0 {
-                255_u8
-            } else {
-                // This will make values closer to max_value appear darker
-                255_u8.saturating_sub(((value as f32 / max_value as f32) * 255.0) as u8)
-            };
-
-            // Only draw non-zero values
-            if value > 0 {
-                svg.push_str(&format!(
-                    r#"<rect x="{}" y="{}" width="{}" height="{}" fill="rgb({},{},{})" />"#,
-                    x as u32 * cell_size,
-                    y as u32 * cell_size,
-                    cell_size,
-                    cell_size,
-                    color_value,
-                    color_value,
-                    color_value
-                ));
-            }
-        }
+// How many robots stand on each tile, darker where there are more
+struct Floor {
+    robots: Grid<i32>,
+    most: i32,
+}
+
+impl Floor {
+    fn new(robots: Grid<i32>) -> Self {
+        let most = robots.iter().map(|(_, &n)| n).max().unwrap_or(1);
+        Floor { robots, most }
     }
+}
 
-    svg.push_str("</svg>");
+impl Render for Floor {
+    fn size(&self) -> (usize, usize) {
+        (self.robots.width(), self.robots.height())
+    }
 
-    let mut file = File::create(filename).expect("Failed to create file");
-    file.write_all(svg.as_bytes()).expect("Failed to write SVG");
+    fn cell(&self, pos: Pos) -> Cell {
+        match self.robots[pos] {
+            n if n <= 0 => Cell::new('.', Layer::Background),
+            n => {
+                let glyph = char::from_digit(n.min(9) as u32, 10).unwrap_or('#');
+                Cell::new(glyph, Layer::Entity).with_color(Rgb::shade(n as f32 / self.most as f32))
+            }
+        }
+    }
 }
 
-pub fn part_two(file: &str) -> i32 {
+pub fn part_two((robots, dimensions): &(Vec<Robot>, Vec2)) -> Result<i32, AocError> {
     // if "most of the robots" arrange themselves into a picture, the safety factor should be low?
-    let content = fs::read_to_string(file).expect("Should be readable");
-
-    let (robots, dimensions) = get_robots(&content);
-
     let mut min_sf = None;
 
     // Guess it happens within the first 10k iterations?
     for t in 1..10000 {
-        let (_, sf) = safety_factor(&robots, &dimensions, t);
+        let (_, sf) = safety_factor(robots, dimensions, t);
         if let Some((_, curr_min)) = min_sf {
             if curr_min > sf {
                 min_sf = Some((t, sf));
@@ -204,20 +184,9 @@ pub fn part_two(file: &str) -> i32 {
 
     let easter_t = min_sf.unwrap().0;
 
-    let (grid, _) = safety_factor(&robots, &dimensions, easter_t);
+    // `animate 14 2 easter-egg.svg` draws the picture
+    animate::capture(|| Floor::new(safety_factor(robots, dimensions, easter_t).0));
 
-    save_grid_as_svg(&grid, &"src/day14/easter-egg.svg", 4);
-
-    easter_t
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn p1() {
-        let result = part_one(&"src/day14/test.txt");
-        assert_eq!(result, 12);
-    }
+    // the answer has always been an i32, which the output formats report
+    i32::try_from(easter_t).map_err(|_| AocError::invalid("the picture took too long to appear"))
 }
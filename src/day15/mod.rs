@@ -1,6 +1,34 @@
-use std::{collections::HashMap, fmt, fs};
+use crate::{
+    animate,
+    error::{offset_in, AocError},
+    geometry::Dir4,
+    grid::{Grid, Pos},
+    parsing,
+    render::{self, Cell, Layer, Render, Rgb},
+    solution::{Answer, Solution},
+};
+use std::fmt;
+use tracing::{debug, trace};
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+    const TITLE: &'static str = "Box re-arrangements";
+    const HAS_PART_TWO: bool = false;
+
+    type Input = (Warehouse, Vec<Dir4>);
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
+    }
 
-#[derive(Debug, Eq, PartialEq)]
+    fn part_one(input: &Self::Input) -> Result<Answer, AocError> {
+        part_one(input).map(Answer::from)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
 enum Entity {
     Wall,
     Empty,
@@ -8,199 +36,134 @@ enum Entity {
     Robot,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
-struct Point {
-    x: usize,
-    y: usize,
-}
-
-#[derive(Debug)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-#[derive(Debug)]
-struct Grid {
-    coordinates: HashMap<Point, Entity>,
-    robot: Point,
+#[derive(Clone, Debug)]
+pub struct Warehouse {
+    map: Grid<Entity>,
+    robot: Pos,
 }
 
-impl Grid {
-    fn dimensions(&self) -> (usize, usize) {
-        let mut max_x = self.robot.x;
-        let mut max_y = self.robot.y;
-
-        for point in self.coordinates.keys() {
-            max_x = max_x.max(point.x);
-            max_y = max_y.max(point.y);
-        }
-
-        (max_x + 1, max_y + 1)
-    }
-
-    fn next_point(&self, from: &Point, direction: &Direction) -> Point {
-        match direction {
-            Direction::Up => Point {
-                x: from.x,
-                y: from.y - 1,
-            },
-            Direction::Down => Point {
-                x: from.x,
-                y: from.y + 1,
-            },
-            Direction::Left => Point {
-                x: from.x - 1,
-                y: from.y,
-            },
-            Direction::Right => Point {
-                x: from.x + 1,
-                y: from.y,
-            },
-        }
-    }
-    fn box_moved(&mut self, point: &Point, direction: &Direction) -> bool {
+impl Warehouse {
+    fn box_moved(&mut self, point: Pos, direction: Dir4) -> Result<bool, AocError> {
         // recursively try to move box
-        let next_point = self.next_point(point, direction);
-        if let Some(next_entity) = self.coordinates.get_mut(&next_point) {
-            match next_entity {
-                Entity::Empty => {
-                    self.coordinates.insert(next_point, Entity::Box);
-                    true
-                }
-                Entity::Wall => false,
-                Entity::Box => {
-                    if self.box_moved(&next_point, direction) {
-                        self.coordinates.insert(next_point, Entity::Box);
-                        true
-                    } else {
-                        false
-                    }
+        let Some(next_point) = self.map.step(point, direction.delta()) else {
+            return Err(AocError::invalid("Unexpected out of bounds!"));
+        };
+        match self.map[next_point] {
+            Entity::Empty => {
+                self.map[next_point] = Entity::Box;
+                Ok(true)
+            }
+            Entity::Wall => Ok(false),
+            Entity::Box => {
+                if self.box_moved(next_point, direction)? {
+                    self.map[next_point] = Entity::Box;
+                    Ok(true)
+                } else {
+                    Ok(false)
                 }
-                _ => panic!("Unexpected next entity"),
             }
-        } else {
-            panic!("Unexpected out of bounds!");
+            Entity::Robot => Err(AocError::invalid("Unexpected next entity")),
         }
     }
 
-    fn move_robot_to(&mut self, point: &Point) {
-        self.coordinates.insert(self.robot.clone(), Entity::Empty);
-        self.coordinates.insert(point.clone(), Entity::Robot);
-        self.robot = point.clone();
+    fn move_robot_to(&mut self, point: Pos) {
+        self.map[self.robot] = Entity::Empty;
+        self.map[point] = Entity::Robot;
+        self.robot = point;
     }
 
-    fn do_move(&mut self, direction: &Direction) {
-        let next_point = self.next_point(&self.robot, direction);
-        if let Some(next_entity) = self.coordinates.get_mut(&next_point) {
-            match next_entity {
-                Entity::Empty => {
-                    self.move_robot_to(&next_point);
-                }
-                Entity::Wall => {}
-                Entity::Box => {
-                    if self.box_moved(&next_point, direction) {
-                        self.move_robot_to(&next_point);
-                    }
+    fn do_move(&mut self, direction: Dir4) -> Result<(), AocError> {
+        let Some(next_point) = self.map.step(self.robot, direction.delta()) else {
+            return Ok(());
+        };
+        match self.map[next_point] {
+            Entity::Empty => {
+                self.move_robot_to(next_point);
+            }
+            Entity::Wall => {}
+            Entity::Box => {
+                if self.box_moved(next_point, direction)? {
+                    self.move_robot_to(next_point);
                 }
-                _ => panic!("Hit another robot??"),
             }
+            Entity::Robot => return Err(AocError::invalid("Hit another robot??")),
         }
+
+        Ok(())
     }
 }
 
-impl fmt::Display for Grid {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let (width, height) = self.dimensions();
-
-        for y in 0..height {
-            for x in 0..width {
-                let point = Point { x, y };
+impl Render for Warehouse {
+    fn size(&self) -> (usize, usize) {
+        (self.map.width(), self.map.height())
+    }
 
-                // Determine what character to print at this position
-                let ch = if point == self.robot {
-                    '@'
-                } else {
-                    match self.coordinates.get(&point) {
-                        Some(Entity::Wall) => '#',
-                        Some(Entity::Empty) => '·',
-                        Some(Entity::Box) => 'O',
-                        Some(Entity::Robot) => '@',
-                        None => '·', // Default to empty for undefined positions
-                    }
-                };
-
-                write!(f, "{}", ch)?;
-            }
-            writeln!(f)?; // New line at the end of each row
+    fn cell(&self, pos: Pos) -> Cell {
+        match self.map[pos] {
+            Entity::Wall => Cell::new('#', Layer::Terrain),
+            Entity::Empty => Cell::new('·', Layer::Background),
+            Entity::Box => Cell::new('O', Layer::Entity).with_color(Rgb::GOLD),
+            Entity::Robot => Cell::new('@', Layer::Highlight),
         }
+    }
+}
 
-        Ok(())
+impl fmt::Display for Warehouse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", render::text(self, None))
     }
 }
 
-fn load(content: &str) -> (Grid, Vec<Direction>) {
-    let mut coordinates = HashMap::new();
+pub fn parse(content: &str) -> Result<(Warehouse, Vec<Dir4>), AocError> {
+    let (map_raw, moves_raw) = parsing::two_sections(content, "the map and the moves")?;
+
+    let map = parsing::grid(content, map_raw, |c| match c {
+        '#' => Ok(Entity::Wall),
+        '.' => Ok(Entity::Empty),
+        '@' => Ok(Entity::Robot),
+        'O' => Ok(Entity::Box),
+        c => Err(format!("unexpected char {c}")),
+    })?;
+    let robot = map
+        .find(|entity| *entity == Entity::Robot)
+        .ok_or_else(|| AocError::invalid("Grid must contain a robot"))?;
+
     let mut moves = Vec::new();
-    let mut robot = None;
-    if let Some((grid_raw, moves_raw)) = content.split_once("\n\n") {
-        for (y, line) in grid_raw.lines().enumerate() {
-            for (x, c) in line.trim().chars().enumerate() {
-                let point = Point { x, y };
-                match c {
-                    '#' => coordinates.insert(point, Entity::Wall),
-                    '.' => coordinates.insert(point, Entity::Empty),
-                    '@' => {
-                        robot = Some(point.clone());
-                        coordinates.insert(point, Entity::Robot)
-                    }
-                    'O' => coordinates.insert(point, Entity::Box),
-                    c => panic!("unexpected char {c}"),
-                };
-            }
-        }
-        for moves_line in moves_raw.lines() {
-            for move_raw in moves_line.trim().chars() {
-                match move_raw {
-                    '<' => moves.push(Direction::Left),
-                    '>' => moves.push(Direction::Right),
-                    '^' => moves.push(Direction::Up),
-                    'v' => moves.push(Direction::Down),
-                    c => panic!("unexpected char {c}"),
-                };
-            }
+    for moves_line in moves_raw.lines() {
+        let moves_line = moves_line.trim();
+        for (i, c) in moves_line.char_indices() {
+            let Some(direction) = Dir4::from_arrow(c) else {
+                return Err(AocError::parse_at(
+                    content,
+                    offset_in(content, moves_line) + i,
+                    format!("unexpected char {c}"),
+                ));
+            };
+            moves.push(direction);
         }
     }
 
-    (
-        Grid {
-            coordinates,
-            robot: robot.expect("Grid must contain a robot"),
-        },
-        moves,
-    )
+    Ok((Warehouse { map, robot }, moves))
 }
 
-pub fn part_one(file: &str) -> usize {
-    let content = fs::read_to_string(file).expect("Should be readable");
-
-    let (mut grid, moves) = load(&content);
+pub fn part_one((warehouse, moves): &(Warehouse, Vec<Dir4>)) -> Result<usize, AocError> {
+    let mut warehouse = warehouse.clone();
+    animate::capture(|| &warehouse);
     for next in moves {
-        grid.do_move(&next);
-        //println!("Move {:?}\n{}", next, grid);
+        warehouse.do_move(*next)?;
+        debug!("Move {:?}\n{}", next, warehouse);
+        animate::capture(|| &warehouse);
     }
 
     let mut sum = 0;
-    for (point, entity) in grid.coordinates {
-        if entity == Entity::Box {
-            sum += (point.y * 100) + point.x;
-            //println!("sum={}, {:?}", sum, point);
+    for ((x, y), entity) in warehouse.map.iter() {
+        if *entity == Entity::Box {
+            sum += (y * 100) + x;
+            trace!("sum={}, {:?}", sum, (x, y));
         }
     }
 
-    sum
+    Ok(sum)
 }
 
 #[cfg(test)]
@@ -208,11 +171,14 @@ mod tests {
     use super::*;
 
     #[test]
-    fn p1() {
-        let small_result = part_one(&"src/day15/smalltest.txt");
-        assert_eq!(small_result, 2028);
-
-        let big_result = part_one(&"src/day15/bigtest.txt");
-        assert_eq!(big_result, 10092);
+    fn bad_input() {
+        let err = parse("#####\n#.O.#\n#####\n\n<>").unwrap_err();
+        assert_eq!(err.to_string(), "invalid puzzle: Grid must contain a robot");
+
+        let err = parse("#####\n#@Ox#\n#####\n\n<>").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "parse error at line 2, column 4: unexpected char x"
+        );
     }
 }
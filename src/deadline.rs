@@ -0,0 +1,61 @@
+use crate::error::AocError;
+use std::{cell::Cell, time::Instant};
+
+thread_local! {
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+// Puts the previous deadline back even if the part panics
+struct Restore(Option<Instant>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        DEADLINE.set(self.0);
+    }
+}
+
+/// Runs `f` with `deadline` as this thread's deadline.
+pub fn with_deadline<T>(deadline: Option<Instant>, f: impl FnOnce() -> T) -> T {
+    let _restore = Restore(DEADLINE.replace(deadline));
+    f()
+}
+
+/// This thread's deadline, to hand on to any threads a part starts itself.
+pub fn current() -> Option<Instant> {
+    DEADLINE.get()
+}
+
+/// A cancellation point for long loops, failing once `deadline` has passed.
+pub fn check_by(deadline: Option<Instant>) -> Result<(), AocError> {
+    match deadline {
+        Some(deadline) if Instant::now() >= deadline => Err(AocError::TimedOut),
+        _ => Ok(()),
+    }
+}
+
+/// A cancellation point against this thread's deadline.
+pub fn check() -> Result<(), AocError> {
+    check_by(current())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn scoped() {
+        assert!(check().is_ok());
+
+        let passed = Some(Instant::now());
+        let later = Some(Instant::now() + Duration::from_secs(60));
+        with_deadline(later, || {
+            assert!(check().is_ok());
+            with_deadline(passed, || {
+                assert!(matches!(check(), Err(AocError::TimedOut)))
+            });
+            assert_eq!(current(), later);
+        });
+        assert_eq!(current(), None);
+    }
+}
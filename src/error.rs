@@ -0,0 +1,107 @@
+use std::{error::Error, fmt, io, str::FromStr};
+
+#[derive(Debug)]
+pub enum AocError {
+    Io(io::Error),
+    /// Malformed input, at a 1-based line and column.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// Input that parsed fine but describes an impossible puzzle.
+    Invalid(String),
+    /// Stopped at a cancellation point after its deadline passed.
+    TimedOut,
+}
+
+impl AocError {
+    /// A parse error at a byte offset into `input`.
+    pub fn parse_at(input: &str, offset: usize, message: impl Into<String>) -> Self {
+        let offset = offset.min(input.len());
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        AocError::Parse {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        AocError::Invalid(message.into())
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AocError::Io(e) => write!(f, "I/O error: {e}"),
+            AocError::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "parse error at line {line}, column {column}: {message}"),
+            AocError::Invalid(message) => write!(f, "invalid puzzle: {message}"),
+            AocError::TimedOut => write!(f, "timed out"),
+        }
+    }
+}
+
+impl Error for AocError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AocError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AocError {
+    fn from(e: io::Error) -> Self {
+        AocError::Io(e)
+    }
+}
+
+/// Where `part`, which must be a slice of `input`, starts within it.
+pub fn offset_in(input: &str, part: &str) -> usize {
+    (part.as_ptr() as usize).saturating_sub(input.as_ptr() as usize)
+}
+
+/// Parses `token`, a slice of `input`, reporting its position on failure.
+pub fn parse_token<T>(input: &str, token: &str) -> Result<T, AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    token
+        .parse()
+        .map_err(|e| AocError::parse_at(input, offset_in(input, token), format!("`{token}`: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions() {
+        let input = "1 2\n3 x4\n";
+        let token = &input[6..8];
+        let err = parse_token::<i32>(input, token).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "parse error at line 2, column 3: `x4`: invalid digit found in string"
+        );
+
+        assert!(matches!(
+            AocError::parse_at(input, 0, "start"),
+            AocError::Parse {
+                line: 1,
+                column: 1,
+                ..
+            }
+        ));
+        assert_eq!(parse_token::<i32>(input, &input[2..3]).unwrap(), 2);
+    }
+}
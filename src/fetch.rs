@@ -0,0 +1,304 @@
+use crate::{cli::FetchArgs, runner::default_input};
+use serde::Deserialize;
+use std::{
+    env,
+    error::Error,
+    fs,
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+const USER_AGENT: &str = "github.com/tmgrask/aoc-2024 input client";
+
+/// Settings read from the config file, each overridden by its environment variable.
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let mut config: Config = if path.exists() {
+            let content = fs::read_to_string(path)?;
+            toml::from_str(&content)
+                .map_err(|e| format!("could not parse {}: {e}", path.display()))?
+        } else {
+            Config::default()
+        };
+
+        if let Ok(session) = env::var("AOC_SESSION") {
+            config.session = Some(session);
+        }
+        if let Ok(base_url) = env::var("AOC_BASE_URL") {
+            config.base_url = Some(base_url);
+        }
+
+        Ok(config)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Fetched {
+    Cached,
+    Downloaded,
+}
+
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+    interval: Duration,
+    last_request: Option<Instant>,
+}
+
+impl Client {
+    /// A client that waits at least `interval` between requests.
+    pub fn new(base_url: &str, session: &str, interval: Duration) -> Self {
+        Client {
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .user_agent(USER_AGENT)
+                .build(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.trim().to_string(),
+            interval,
+            last_request: None,
+        }
+    }
+
+    fn throttle(&mut self) {
+        if let Some(last) = self.last_request {
+            let waited = last.elapsed();
+            if waited < self.interval {
+                thread::sleep(self.interval - waited);
+            }
+        }
+        self.last_request = Some(Instant::now());
+    }
+
+    pub fn download(&mut self, day: u8) -> Result<String, Box<dyn Error>> {
+        self.throttle();
+
+        let url = format!("{}/2024/day/{day}/input", self.base_url);
+        let response = self
+            .agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call();
+
+        match response {
+            Ok(response) => Ok(response.into_string()?),
+            Err(ureq::Error::Status(400, _)) => Err("the server rejected the session token".into()),
+            Err(ureq::Error::Status(404, _)) => {
+                Err(format!("day {day} is not available yet").into())
+            }
+            Err(ureq::Error::Status(429, _)) => {
+                Err("rate limited by the server, try again later".into())
+            }
+            Err(ureq::Error::Status(code, _)) => {
+                Err(format!("fetching {url} failed with status {code}").into())
+            }
+            Err(e) => Err(format!("fetching {url} failed: {e}").into()),
+        }
+    }
+
+    /// Posts an answer, returning the page the server replies with.
+    pub fn submit(&mut self, day: u8, part: u8, answer: &str) -> Result<String, Box<dyn Error>> {
+        self.throttle();
+
+        let url = format!("{}/2024/day/{day}/answer", self.base_url);
+        let response = self
+            .agent
+            .post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[("level", &part.to_string()), ("answer", answer)]);
+
+        match response {
+            Ok(response) => Ok(response.into_string()?),
+            Err(ureq::Error::Status(code, _)) => {
+                Err(format!("submitting to {url} failed with status {code}").into())
+            }
+            Err(e) => Err(format!("submitting to {url} failed: {e}").into()),
+        }
+    }
+
+    /// Downloads a day's input to `path`, unless it is already there.
+    pub fn fetch(&mut self, day: u8, path: &Path) -> Result<Fetched, Box<dyn Error>> {
+        if path.is_file() {
+            return Ok(Fetched::Cached);
+        }
+
+        let input = self.download(day)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, input)?;
+
+        Ok(Fetched::Downloaded)
+    }
+}
+
+/// A client for the server named by `base_url`, the config file or the default.
+pub fn connect(config: &Path, base_url: Option<&str>) -> Result<Client, Box<dyn Error>> {
+    let settings = Config::load(config)?;
+    let session = settings.session.ok_or_else(|| {
+        format!(
+            "no session token, set AOC_SESSION or `session` in {}",
+            config.display()
+        )
+    })?;
+    let base_url = base_url
+        .map(str::to_string)
+        .or(settings.base_url)
+        .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+
+    Ok(Client::new(&base_url, &session, Duration::from_secs(3)))
+}
+
+pub fn fetch(args: &FetchArgs) -> Result<(), Box<dyn Error>> {
+    // cached days need neither a session token nor the server
+    let (cached, missing): (Vec<u8>, Vec<u8>) = args
+        .days
+        .clone()
+        .partition(|day| default_input(*day).is_file());
+    for day in cached {
+        println!("\tDay {day}: already at {}", default_input(day).display());
+    }
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let mut client = connect(&args.config, args.base_url.as_deref())?;
+    for day in missing {
+        let path = default_input(day);
+        match client.fetch(day, &path)? {
+            Fetched::Cached => println!("\tDay {day}: already at {}", path.display()),
+            Fetched::Downloaded => println!("\tDay {day}: saved to {}", path.display()),
+        }
+    }
+
+    Ok(())
+}
+
+/// A stand-in for the puzzle server, for testing the client end to end.
+#[cfg(test)]
+pub mod mock {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+    };
+
+    #[derive(Debug, PartialEq)]
+    pub struct Request {
+        pub line: String,
+        pub cookie: String,
+        pub body: String,
+    }
+
+    /// Answers each connection with the next canned status and body, sending
+    /// back the request it was given.
+    pub fn serve(responses: Vec<(u16, &'static str)>) -> (String, mpsc::Receiver<Request>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+
+                let mut cookie = String::new();
+                let mut length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    let (name, value) = header.split_once(':').unwrap();
+                    match name.to_ascii_lowercase().as_str() {
+                        "cookie" => cookie = value.trim().to_string(),
+                        "content-length" => length = value.trim().parse().unwrap(),
+                        _ => {}
+                    }
+                }
+                let mut request_body = vec![0; length];
+                reader.read_exact(&mut request_body).unwrap();
+
+                tx.send(Request {
+                    line: line.trim().to_string(),
+                    cookie,
+                    body: String::from_utf8(request_body).unwrap(),
+                })
+                .unwrap();
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+
+        (url, rx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mock::serve, *};
+
+    #[test]
+    fn fetches_once() {
+        let (url, requests) = serve(vec![(200, "1 2\n3 4\n")]);
+        let path = env::temp_dir().join(format!("aoc-fetch-{}/input.txt", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut client = Client::new(&url, "abc123\n", Duration::ZERO);
+        assert_eq!(client.fetch(1, &path).unwrap(), Fetched::Downloaded);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1 2\n3 4\n");
+        let request = requests.recv().unwrap();
+        assert_eq!(request.line, "GET /2024/day/1/input HTTP/1.1");
+        assert_eq!(request.cookie, "session=abc123");
+
+        // The server would refuse a second connection, so this must be cached
+        assert_eq!(client.fetch(1, &path).unwrap(), Fetched::Cached);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn reports_server_errors() {
+        let (url, _requests) = serve(vec![(400, "Please log in"), (429, ""), (404, "")]);
+        let mut client = Client::new(&url, "expired", Duration::ZERO);
+
+        let message = |r: Result<String, Box<dyn Error>>| r.unwrap_err().to_string();
+        assert_eq!(
+            message(client.download(1)),
+            "the server rejected the session token"
+        );
+        assert_eq!(
+            message(client.download(2)),
+            "rate limited by the server, try again later"
+        );
+        assert_eq!(message(client.download(25)), "day 25 is not available yet");
+    }
+
+    #[test]
+    fn throttles() {
+        let (url, _requests) = serve(vec![(200, "a"), (200, "b")]);
+        let mut client = Client::new(&url, "abc", Duration::from_millis(200));
+
+        let start = Instant::now();
+        client.download(1).unwrap();
+        client.download(2).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+}
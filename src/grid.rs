@@ -0,0 +1,243 @@
+use crate::{
+    error::AocError,
+    geometry::{Dir4, Dir8, Vec2},
+};
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+/// A cell's `(x, y)` position, with `y` counting rows down from the top.
+pub type Pos = (usize, usize);
+
+/// A rectangle of cells stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Parses a character map, turning each character into a cell with
+    /// `cell`, which can reject it with a message. Every row must be as wide
+    /// as the first.
+    pub fn parse(
+        input: &str,
+        mut cell: impl FnMut(char) -> Result<T, String>,
+    ) -> Result<Self, AocError> {
+        let mut width = None;
+        let mut cells = Vec::new();
+        let mut height = 0;
+
+        for (y, line) in input.lines().enumerate() {
+            let mut row_width = 0;
+            for (x, c) in line.chars().enumerate() {
+                cells.push(cell(c).map_err(|message| AocError::Parse {
+                    line: y + 1,
+                    column: x + 1,
+                    message,
+                })?);
+                row_width += 1;
+            }
+
+            let width = *width.get_or_insert(row_width);
+            if row_width != width {
+                return Err(AocError::Parse {
+                    line: y + 1,
+                    column: row_width.min(width) + 1,
+                    message: format!("expected a row of {width} cells, found {row_width}"),
+                });
+            }
+            height += 1;
+        }
+
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): Pos) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    pub fn get_mut(&mut self, (x, y): Pos) -> Option<&mut T> {
+        (x < self.width && y < self.height).then(|| &mut self.cells[y * self.width + x])
+    }
+
+    /// The position of `v`, if it is on the grid.
+    pub fn pos(&self, v: Vec2) -> Option<Pos> {
+        v.to_pos()
+            .filter(|&(x, y)| x < self.width && y < self.height)
+    }
+
+    /// The position `delta` away from `pos`, if that is still on the grid.
+    pub fn step(&self, pos: Pos, delta: Vec2) -> Option<Pos> {
+        self.pos(Vec2::from(pos) + delta)
+    }
+
+    /// The up to four orthogonal neighbours of `pos` that are on the grid.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Dir4::ALL
+            .into_iter()
+            .filter_map(move |dir| self.step(pos, dir.delta()))
+    }
+
+    /// The up to eight neighbours of `pos`, diagonals included, on the grid.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Dir8::ALL
+            .into_iter()
+            .filter_map(move |dir| self.step(pos, dir.delta()))
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| (i % width, i / width))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    /// The cells from `start` onwards, taking steps of `delta` until the
+    /// edge. A diagonal delta walks a diagonal.
+    pub fn ray(&self, start: Pos, delta: Vec2) -> impl Iterator<Item = (Pos, &T)> {
+        std::iter::successors(self.get(start).map(|_| start), move |&pos| {
+            self.step(pos, delta)
+        })
+        .map(|pos| (pos, &self[pos]))
+    }
+
+    /// The position of the first cell, row by row, that matches.
+    pub fn find(&self, mut matches: impl FnMut(&T) -> bool) -> Option<Pos> {
+        self.iter()
+            .find(|(_, cell)| matches(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the grid"))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..self.height {
+            for cell in self.row(y) {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        Grid::parse("ab.\n.c^\n", Ok).unwrap()
+    }
+
+    #[test]
+    fn parses() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], '^');
+        assert_eq!(grid.find(|c| *c == '^'), Some((2, 1)));
+        assert_eq!(grid.to_string(), "ab.\n.c^\n");
+
+        let err = Grid::parse("..\n.x\n", |c| match c {
+            '.' => Ok(()),
+            _ => Err(format!("unexpected `{c}`")),
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "parse error at line 2, column 2: unexpected `x`"
+        );
+        let err = Grid::parse("...\n..\n", Ok).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "parse error at line 2, column 3: expected a row of 3 cells, found 2"
+        );
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = grid();
+        assert_eq!(
+            grid.neighbors4((0, 0)).collect::<Vec<_>>(),
+            [(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbors8((0, 0)).count(), 3);
+        assert_eq!(grid.neighbors8((1, 1)).count(), 5);
+        assert_eq!(grid.step((0, 0), Dir4::Left.delta()), None);
+    }
+
+    #[test]
+    fn lines() {
+        let grid = grid();
+        assert_eq!(grid.row(1), ['.', 'c', '^']);
+        assert_eq!(grid.column(1).collect::<String>(), "bc");
+        assert_eq!(
+            grid.ray((0, 0), Dir8::SE.delta())
+                .map(|(_, c)| *c)
+                .collect::<String>(),
+            "ac"
+        );
+        assert_eq!(grid.ray((3, 0), Dir8::E.delta()).count(), 0);
+    }
+}
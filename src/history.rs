@@ -0,0 +1,199 @@
+use crate::{
+    bench::percent_change,
+    cli::HistoryArgs,
+    runner::{default_input, part_label, Record, Status},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// One timed part from one run, stored as a line of JSON.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Entry {
+    pub commit: String,
+    pub timestamp: u64,
+    pub day: u8,
+    pub part: u8,
+    pub duration_ms: f64,
+}
+
+fn current_commit() -> String {
+    Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+// Only runs against the real inputs are comparable over time
+pub fn append(path: &Path, records: &[Record]) -> Result<(), Box<dyn Error>> {
+    let commit = current_commit();
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    let mut lines = String::new();
+    for record in records {
+        if record.status != Status::Ok || record.input != default_input(record.day) {
+            continue;
+        }
+        let Some(duration_ms) = record.duration_ms else {
+            continue;
+        };
+        let entry = Entry {
+            commit: commit.clone(),
+            timestamp,
+            day: record.day,
+            part: record.part,
+            duration_ms,
+        };
+        lines.push_str(&serde_json::to_string(&entry)?);
+        lines.push('\n');
+    }
+
+    if !lines.is_empty() {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(lines.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn load(path: &Path) -> Result<Vec<Entry>, Box<dyn Error>> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("could not read {}: {e}", path.display()))?;
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| format!("{}:{}: {e}", path.display(), i + 1).into())
+        })
+        .collect()
+}
+
+/// Compares the latest timing against the median of up to `window` timings
+/// before it. Returns the baseline and the percentage change, which is
+/// undefined for a zero baseline.
+pub fn against_baseline(durations: &[f64], window: usize) -> Option<(f64, Option<f64>)> {
+    let (latest, previous) = durations.split_last()?;
+    let mut baseline: Vec<f64> = previous[previous.len().saturating_sub(window)..].to_vec();
+    if baseline.is_empty() {
+        return None;
+    }
+    baseline.sort_by(f64::total_cmp);
+    let mid = baseline.len() / 2;
+    let median = if baseline.len().is_multiple_of(2) {
+        (baseline[mid - 1] + baseline[mid]) / 2.0
+    } else {
+        baseline[mid]
+    };
+
+    Some((median, percent_change(median, *latest)))
+}
+
+pub fn sparkline(durations: &[f64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let min = durations.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = durations.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    durations
+        .iter()
+        .map(|d| {
+            if max > min {
+                BARS[((d - min) / (max - min) * 7.0).round() as usize]
+            } else {
+                BARS[0]
+            }
+        })
+        .collect()
+}
+
+pub fn history(args: &HistoryArgs) -> Result<(), Box<dyn Error>> {
+    let entries = load(&args.file)?;
+
+    let mut parts: BTreeMap<(u8, u8), Vec<&Entry>> = BTreeMap::new();
+    for entry in &entries {
+        if args
+            .days
+            .as_ref()
+            .is_none_or(|days| days.contains(&entry.day))
+        {
+            parts
+                .entry((entry.day, entry.part))
+                .or_default()
+                .push(entry);
+        }
+    }
+
+    let mut slower = 0;
+    for ((day, part), entries) in parts {
+        let durations: Vec<f64> = entries.iter().map(|e| e.duration_ms).collect();
+        let latest = entries[entries.len() - 1];
+        let recent = &durations[durations.len().saturating_sub(args.window * 2)..];
+
+        print!(
+            "\t{} {} {:>9.3}ms @ {}",
+            part_label(day, part),
+            sparkline(recent),
+            latest.duration_ms,
+            latest.commit
+        );
+        match against_baseline(&durations, args.window) {
+            Some((baseline, Some(change))) => {
+                let mark = if change > args.threshold {
+                    slower += 1;
+                    " SLOWER"
+                } else {
+                    ""
+                };
+                println!("  baseline {baseline:>9.3}ms {change:>+7.1}%{mark}");
+            }
+            Some((baseline, None)) => println!("  baseline {baseline:>9.3}ms     n/a"),
+            None => println!(),
+        }
+    }
+
+    if slower > 0 {
+        println!(
+            "\n\t{slower} part(s) more than {}% slower than their baseline",
+            args.threshold
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn baseline() {
+        assert_eq!(against_baseline(&[], 5), None);
+        assert_eq!(against_baseline(&[10.0], 5), None);
+        assert_eq!(against_baseline(&[10.0, 12.0], 0), None);
+        assert_eq!(against_baseline(&[10.0, 12.0], 5), Some((10.0, Some(20.0))));
+        assert_eq!(against_baseline(&[0.0, 0.0, 0.5], 5), Some((0.0, None)));
+        // Only the last `window` timings before the latest count
+        assert_eq!(
+            against_baseline(&[100.0, 8.0, 10.0, 12.0, 5.0], 3),
+            Some((10.0, Some(-50.0)))
+        );
+    }
+
+    #[test]
+    fn sparklines() {
+        assert_eq!(sparkline(&[1.0, 8.0, 4.5]), "▁█▅");
+        assert_eq!(sparkline(&[2.0, 2.0]), "▁▁");
+    }
+}
@@ -0,0 +1,55 @@
+pub mod animate;
+pub mod answers;
+pub mod bench;
+pub mod cli;
+pub mod deadline;
+pub mod error;
+pub mod fetch;
+pub mod geometry;
+pub mod grid;
+pub mod history;
+pub mod memory;
+pub mod output;
+pub mod params;
+pub mod parsing;
+pub mod render;
+pub mod repl;
+pub mod runner;
+pub mod scaffold;
+pub mod search;
+#[macro_use]
+pub mod solution;
+pub mod submit;
+pub mod trace;
+pub mod watch;
+
+pub use error::AocError;
+pub use solution::{Answer, DynSolution, Solution};
+
+solutions! {
+    day01::Day01,
+    day02::Day02,
+    day03::Day03,
+    day04::Day04,
+    day05::Day05,
+    day06::Day06,
+    day07::Day07,
+    day08::Day08,
+    day09::Day09,
+    day10::Day10,
+    day11::Day11,
+    day12::Day12,
+    day13::Day13,
+    day14::Day14,
+    day15::Day15,
+}
+
+/// Evaluates an expression, returning its result with how long it took.
+#[macro_export]
+macro_rules! time_it {
+    ($e:expr) => {{
+        let start = std::time::Instant::now();
+        let result = $e;
+        (result, start.elapsed())
+    }};
+}
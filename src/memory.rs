@@ -0,0 +1,170 @@
+use serde::Serialize;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering::Relaxed},
+};
+
+/// Whether this build counts allocations, see the `alloc-stats` feature.
+pub const ENABLED: bool = cfg!(feature = "alloc-stats");
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator, keeping process-wide totals of what it hands out.
+pub struct Counting;
+
+fn allocated(size: usize) {
+    ALLOCATIONS.fetch_add(1, Relaxed);
+    BYTES.fetch_add(size, Relaxed);
+    let live = LIVE.fetch_add(size, Relaxed) + size;
+    PEAK.fetch_max(live, Relaxed);
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE.fetch_sub(layout.size(), Relaxed);
+    }
+
+    // A move to a bigger block counts as a fresh allocation of the new size
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            LIVE.fetch_sub(layout.size(), Relaxed);
+            allocated(new_size);
+        }
+        new
+    }
+}
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// What one part allocated while it ran.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AllocStats {
+    pub allocations: usize,
+    pub bytes: usize,
+    /// Most bytes live at once, over what was live when the part started
+    pub peak_bytes: usize,
+}
+
+impl fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} allocs, {} total, {} peak",
+            self.allocations,
+            ByteSize(self.bytes),
+            ByteSize(self.peak_bytes)
+        )
+    }
+}
+
+struct ByteSize(usize);
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut size = self.0 as f64;
+        for unit in ["B", "KiB", "MiB"] {
+            if size < 1024.0 {
+                return if unit == "B" {
+                    write!(f, "{size}{unit}")
+                } else {
+                    write!(f, "{size:.1}{unit}")
+                };
+            }
+            size /= 1024.0;
+        }
+        write!(f, "{size:.1}GiB")
+    }
+}
+
+struct Snapshot {
+    allocations: usize,
+    bytes: usize,
+    live: usize,
+}
+
+impl Snapshot {
+    // Also restarts the peak, so only one measurement can run at a time
+    fn take() -> Self {
+        let live = LIVE.load(Relaxed);
+        PEAK.store(live, Relaxed);
+        Snapshot {
+            allocations: ALLOCATIONS.load(Relaxed),
+            bytes: BYTES.load(Relaxed),
+            live,
+        }
+    }
+
+    fn since(&self) -> AllocStats {
+        AllocStats {
+            allocations: ALLOCATIONS.load(Relaxed) - self.allocations,
+            bytes: BYTES.load(Relaxed) - self.bytes,
+            peak_bytes: PEAK.load(Relaxed).saturating_sub(self.live),
+        }
+    }
+}
+
+/// Runs `f`, returning what it allocated when this build counts allocations.
+/// The totals are process wide, so anything else allocating meanwhile,
+/// including other parts, is counted too.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    if !ENABLED {
+        return (f(), None);
+    }
+    let start = Snapshot::take();
+    let result = f();
+    (result, Some(start.since()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts() {
+        let layout = Layout::from_size_align(4096, 8).unwrap();
+        let start = Snapshot::take();
+        unsafe {
+            let ptr = Counting.alloc(layout);
+            let ptr = Counting.realloc(ptr, layout, 8192);
+            Counting.dealloc(ptr, Layout::from_size_align(8192, 8).unwrap());
+        }
+        let stats = start.since();
+        assert!(stats.allocations >= 2);
+        assert!(stats.bytes >= 4096 + 8192);
+        assert!(stats.peak_bytes >= 8192);
+    }
+
+    #[test]
+    fn sizes() {
+        let stats = AllocStats {
+            allocations: 3,
+            bytes: 3 * 1024 * 1024 / 2,
+            peak_bytes: 512,
+        };
+        assert_eq!(stats.to_string(), "3 allocs, 1.5MiB total, 512B peak");
+    }
+}
@@ -0,0 +1,231 @@
+use crate::{
+    answers::Verdict,
+    cli::Format,
+    memory::AllocStats,
+    runner::{part_label, Record, Status},
+};
+use std::{
+    collections::HashSet,
+    io::{self, Write},
+    time::Duration,
+};
+
+pub fn write(w: &mut impl Write, format: Format, records: &[Record]) -> io::Result<()> {
+    match format {
+        Format::Text => write_text(w, records),
+        Format::Json => write_json(w, records),
+        Format::Csv => write_csv(w, records),
+    }
+}
+
+fn verdict_mark(record: &Record) -> String {
+    match (record.verdict, &record.expected) {
+        (Some(Verdict::Pass), _) => " \u{2714}".to_string(),
+        (Some(Verdict::Fail), Some(expected)) => format!(" \u{2718} (expected {expected})"),
+        (Some(Verdict::Fail), None) => " \u{2718}".to_string(),
+        (Some(Verdict::Unknown), _) => " ?".to_string(),
+        (None, _) => String::new(),
+    }
+}
+
+fn write_text(w: &mut impl Write, records: &[Record]) -> io::Result<()> {
+    writeln!(w, "\n\tAdvent of Code 2024\n")?;
+
+    let mut last_day = None;
+    for record in records {
+        if last_day != Some(record.day) {
+            if last_day.is_some() {
+                writeln!(w)?;
+            }
+            writeln!(w, "\tDay {}: {}", record.day, record.title)?;
+        }
+        last_day = Some(record.day);
+
+        let message = record.message.as_deref().unwrap_or_default();
+        match record.status {
+            Status::Ok => writeln!(
+                w,
+                "\t{} in {:>6.1?}ms{} -> {}{}",
+                part_label(record.day, record.part),
+                record.duration_ms.unwrap_or_default(),
+                record
+                    .alloc
+                    .map(|alloc| format!(" ({alloc})"))
+                    .unwrap_or_default(),
+                record.answer.as_deref().unwrap_or_default(),
+                verdict_mark(record),
+            )?,
+            Status::Failed => writeln!(
+                w,
+                "\t{} failed: {message}",
+                part_label(record.day, record.part)
+            )?,
+            Status::Missing => writeln!(
+                w,
+                "\t{} missing input {}: {message}",
+                part_label(record.day, record.part),
+                record.input.display()
+            )?,
+            Status::TimedOut => writeln!(w, "\t{} {message}", part_label(record.day, record.part))?,
+        }
+    }
+
+    Ok(())
+}
+
+pub fn write_totals(w: &mut impl Write, records: &[Record], wall: Duration) -> io::Result<()> {
+    let summed: f64 = records.iter().filter_map(|r| r.duration_ms).sum();
+    // a day's parts share one parse, so count it once
+    let mut parsed = HashSet::new();
+    let parsing: f64 = records
+        .iter()
+        .filter(|r| parsed.insert((r.day, &r.input)))
+        .filter_map(|r| r.parse_ms)
+        .sum();
+    writeln!(
+        w,
+        "\n\tTotal {:.1}ms wall clock, {summed:.1}ms summed over parts, {parsing:.1}ms parsing",
+        wall.as_secs_f64() * 1000.0
+    )
+}
+
+fn write_json(w: &mut impl Write, records: &[Record]) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *w, records)?;
+    writeln!(w)
+}
+
+// Quote a field only when it would otherwise break the row
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn write_csv(w: &mut impl Write, records: &[Record]) -> io::Result<()> {
+    writeln!(
+        w,
+        "day,part,title,status,answer,answer_type,duration_ms,input,message,verdict,expected,allocations,alloc_bytes,peak_bytes,parse_ms"
+    )?;
+
+    for record in records {
+        let status = match record.status {
+            Status::Ok => "ok",
+            Status::Failed => "failed",
+            Status::Missing => "missing",
+            Status::TimedOut => "timed_out",
+        };
+        let verdict = match record.verdict {
+            Some(Verdict::Pass) => "pass",
+            Some(Verdict::Fail) => "fail",
+            Some(Verdict::Unknown) => "unknown",
+            None => "",
+        };
+        let alloc_field = |field: fn(&AllocStats) -> usize| {
+            record
+                .alloc
+                .as_ref()
+                .map(|a| field(a).to_string())
+                .unwrap_or_default()
+        };
+        let fields = [
+            record.day.to_string(),
+            record.part.to_string(),
+            record.title.to_string(),
+            status.to_string(),
+            record.answer.clone().unwrap_or_default(),
+            record.answer_type.unwrap_or_default().to_string(),
+            record
+                .duration_ms
+                .map(|ms| format!("{ms:.3}"))
+                .unwrap_or_default(),
+            record.input.display().to_string(),
+            record.message.clone().unwrap_or_default(),
+            verdict.to_string(),
+            record.expected.clone().unwrap_or_default(),
+            alloc_field(|a| a.allocations),
+            alloc_field(|a| a.bytes),
+            alloc_field(|a| a.peak_bytes),
+            record
+                .parse_ms
+                .map(|ms| format!("{ms:.3}"))
+                .unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        writeln!(w, "{}", row.join(","))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                day: 7,
+                part: 2,
+                title: "Kenken",
+                status: Status::Ok,
+                answer: Some("11387".to_string()),
+                answer_type: Some("i64"),
+                duration_ms: Some(1.5),
+                parse_ms: Some(0.125),
+                alloc: Some(AllocStats {
+                    allocations: 12,
+                    bytes: 4096,
+                    peak_bytes: 1024,
+                }),
+                input: PathBuf::from("src/day07/test.txt"),
+                message: None,
+                verdict: Some(Verdict::Fail),
+                expected: Some("11388".to_string()),
+            },
+            Record {
+                day: 15,
+                part: 1,
+                title: "Box re-arrangements",
+                status: Status::Failed,
+                answer: None,
+                answer_type: None,
+                duration_ms: Some(0.25),
+                parse_ms: None,
+                alloc: None,
+                input: PathBuf::from("src/day15/input.txt"),
+                message: Some("unexpected char \"x\", bad".to_string()),
+                verdict: None,
+                expected: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn csv() {
+        let mut out = Vec::new();
+        write(&mut out, Format::Csv, &records()).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "day,part,title,status,answer,answer_type,duration_ms,input,message,verdict,expected,allocations,alloc_bytes,peak_bytes,parse_ms\n\
+             7,2,Kenken,ok,11387,i64,1.500,src/day07/test.txt,,fail,11388,12,4096,1024,0.125\n\
+             15,1,Box re-arrangements,failed,,,0.250,src/day15/input.txt,\"unexpected char \"\"x\"\", bad\",,,,,,\n"
+        );
+    }
+
+    #[test]
+    fn json() {
+        let mut out = Vec::new();
+        write(&mut out, Format::Json, &records()).unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed[0]["answer"], "11387");
+        assert_eq!(parsed[0]["status"], "ok");
+        assert_eq!(parsed[0]["verdict"], "fail");
+        assert_eq!(parsed[0]["alloc"]["peak_bytes"], 1024);
+        assert_eq!(parsed[0]["parse_ms"], 0.125);
+        assert_eq!(parsed[1]["status"], "failed");
+        assert_eq!(parsed[1]["answer"], serde_json::Value::Null);
+    }
+}
@@ -0,0 +1,75 @@
+use std::{cell::RefCell, collections::HashMap};
+
+/// A number a day reads while solving, which the REPL can override.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Param {
+    pub name: &'static str,
+    pub about: &'static str,
+}
+
+#[derive(Default)]
+struct Scope {
+    values: HashMap<String, i64>,
+    read: Vec<(&'static str, i64)>,
+}
+
+thread_local! {
+    static SCOPE: RefCell<Option<Scope>> = const { RefCell::new(None) };
+}
+
+// Puts the previous scope back even if the part panics
+struct Restore(Option<Scope>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        SCOPE.set(self.0.take());
+    }
+}
+
+/// Runs `f` with `values` overriding parameter defaults on this thread,
+/// returning each parameter it read and the value it got.
+pub fn with<T>(
+    values: &HashMap<String, i64>,
+    f: impl FnOnce() -> T,
+) -> (T, Vec<(&'static str, i64)>) {
+    let scope = Scope {
+        values: values.clone(),
+        read: Vec::new(),
+    };
+    let mut restore = Restore(SCOPE.replace(Some(scope)));
+    let result = f();
+    let read = SCOPE
+        .replace(restore.0.take())
+        .map(|scope| scope.read)
+        .unwrap_or_default();
+    (result, read)
+}
+
+/// The value of parameter `name`, or `default` when it isn't overridden.
+pub fn get(name: &'static str, default: i64) -> i64 {
+    SCOPE.with_borrow_mut(|scope| match scope {
+        Some(scope) => {
+            let value = scope.values.get(name).copied().unwrap_or(default);
+            scope.read.push((name, value));
+            value
+        }
+        None => default,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides() {
+        assert_eq!(get("blinks", 25), 25);
+
+        let values = HashMap::from([("blinks".to_string(), 6)]);
+        let (value, read) = with(&values, || get("blinks", 25) + get("seconds", 100));
+        assert_eq!(value, 106);
+        assert_eq!(read, [("blinks", 6), ("seconds", 100)]);
+
+        assert_eq!(get("blinks", 25), 25);
+    }
+}
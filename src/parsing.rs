@@ -0,0 +1,251 @@
+// Each helper takes the whole input along with the slice of it to parse, so
+// errors point at a line and column of the input rather than of the slice
+
+use crate::{
+    error::{offset_in, parse_token, AocError},
+    grid::Grid,
+};
+use regex::{Captures, Regex};
+use std::{fmt, str::FromStr};
+
+/// Values separated by whitespace, like `3   4` or `7 6 4 2 1`.
+pub fn words<T>(input: &str, text: &str) -> Result<Vec<T>, AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    text.split_whitespace()
+        .map(|word| parse_token(input, word))
+        .collect()
+}
+
+/// Values separated by `separator`, like `75,47,61`, each trimmed.
+pub fn separated<T>(input: &str, text: &str, separator: char) -> Result<Vec<T>, AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    text.split(separator)
+        .map(|value| parse_token(input, value.trim()))
+        .collect()
+}
+
+/// Every signed integer in `text`, ignoring whatever lies between them.
+pub fn ints<T>(input: &str, text: &str) -> Result<Vec<T>, AocError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let number = Regex::new(r"-?\d+").unwrap();
+    number
+        .find_iter(text)
+        .map(|found| parse_token(input, found.as_str()))
+        .collect()
+}
+
+/// The runs of non-blank lines in `input`, without their trailing newline.
+pub fn sections(input: &str) -> Vec<&str> {
+    let mut sections = Vec::new();
+    let mut start = None;
+    let mut end = 0;
+
+    for line in input.lines() {
+        let offset = offset_in(input, line);
+        if line.trim().is_empty() {
+            if let Some(start) = start.take() {
+                sections.push(&input[start..end]);
+            }
+        } else {
+            start.get_or_insert(offset);
+            end = offset + line.len();
+        }
+    }
+    if let Some(start) = start {
+        sections.push(&input[start..end]);
+    }
+
+    sections
+}
+
+/// Exactly two sections, where `between` describes what the blank line
+/// separates.
+pub fn two_sections<'a>(input: &'a str, between: &str) -> Result<(&'a str, &'a str), AocError> {
+    match sections(input)[..] {
+        [first, second] => Ok((first, second)),
+        [_, _, third, ..] => Err(AocError::parse_at(
+            input,
+            offset_in(input, third),
+            format!("expected only {between}"),
+        )),
+        _ => Err(AocError::parse_at(
+            input,
+            input.trim_end().len(),
+            format!("expected a blank line between {between}"),
+        )),
+    }
+}
+
+/// A line like `190: 10 19`, with whitespace-separated values after the
+/// colon.
+pub fn key_values<K, V>(input: &str, line: &str) -> Result<(K, Vec<V>), AocError>
+where
+    K: FromStr,
+    K::Err: fmt::Display,
+    V: FromStr,
+    V::Err: fmt::Display,
+{
+    let Some((key, values)) = line.split_once(':') else {
+        return Err(AocError::parse_at(
+            input,
+            offset_in(input, line),
+            "expected `key: values`",
+        ));
+    };
+    Ok((parse_token(input, key.trim())?, words(input, values)?))
+}
+
+/// A grid from `section`, turning each character into a cell with `cell`.
+pub fn grid<T>(
+    input: &str,
+    section: &str,
+    cell: impl FnMut(char) -> Result<T, String>,
+) -> Result<Grid<T>, AocError> {
+    Grid::parse(section, cell).map_err(|e| match e {
+        // the grid counts lines from the top of the section
+        AocError::Parse {
+            line,
+            column,
+            message,
+        } => AocError::Parse {
+            line: line + input[..offset_in(input, section)].matches('\n').count(),
+            column,
+            message,
+        },
+        e => e,
+    })
+}
+
+/// A grid of the characters in `section`.
+pub fn char_grid(input: &str, section: &str) -> Result<Grid<char>, AocError> {
+    grid(input, section, Ok)
+}
+
+/// A `text` matched against a regex with named groups, like
+/// `p=(?P<px>\d+),(?P<py>\d+)`.
+pub struct Record<'a> {
+    input: &'a str,
+    captures: Captures<'a>,
+}
+
+/// Matches `text` against `pattern`, failing with `expected` when it doesn't.
+pub fn record<'a>(
+    input: &'a str,
+    text: &'a str,
+    pattern: &Regex,
+    expected: &str,
+) -> Result<Record<'a>, AocError> {
+    match pattern.captures(text) {
+        Some(captures) => Ok(Record { input, captures }),
+        None => Err(AocError::parse_at(
+            input,
+            offset_in(input, text),
+            format!("expected {expected}"),
+        )),
+    }
+}
+
+impl Record<'_> {
+    /// The text of group `name`.
+    ///
+    /// Panics if the pattern has no group `name`, which is a bug in the
+    /// pattern rather than the input.
+    pub fn str(&self, name: &str) -> &str {
+        &self.captures[name]
+    }
+
+    /// The value of group `name`.
+    pub fn get<T>(&self, name: &str) -> Result<T, AocError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        parse_token(self.input, self.str(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values() {
+        let input = "3   4\n75, 47,61\nx: -1 2 3\n";
+        let lines: Vec<&str> = input.lines().collect();
+        assert_eq!(words::<i32>(input, lines[0]).unwrap(), [3, 4]);
+        assert_eq!(
+            separated::<i32>(input, lines[1], ',').unwrap(),
+            [75, 47, 61]
+        );
+        assert_eq!(ints::<i32>(input, lines[2]).unwrap(), [-1, 2, 3]);
+
+        let err = key_values::<i32, i32>(input, lines[2]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "parse error at line 3, column 1: `x`: invalid digit found in string"
+        );
+        let err = key_values::<i32, i32>(input, lines[0]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "parse error at line 1, column 1: expected `key: values`"
+        );
+    }
+
+    #[test]
+    fn splits_sections() {
+        let input = "#.\n.#\n\n\n<>\n^v\n";
+        assert_eq!(sections(input), ["#.\n.#", "<>\n^v"]);
+        assert_eq!(sections("a\r\n\r\nb\r\n"), ["a", "b"]);
+        assert!(sections("\n\n").is_empty());
+
+        let (map, _) = two_sections(input, "the map and the moves").unwrap();
+        assert_eq!(char_grid(input, map).unwrap().width(), 2);
+
+        let err = two_sections("#.\n.#\n", "the map and the moves").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "parse error at line 2, column 3: expected a blank line between the map and the moves"
+        );
+
+        let moves = sections(input)[1];
+        let err = grid(input, moves, |c| match c {
+            '<' | '>' => Ok(()),
+            _ => Err(format!("unexpected char {c}")),
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "parse error at line 6, column 1: unexpected char ^"
+        );
+    }
+
+    #[test]
+    fn records() {
+        let input = "p=0,4 v=3,-3\np=6,3 v=-1\n";
+        let lines: Vec<&str> = input.lines().collect();
+        let robot =
+            Regex::new(r"^p=(?P<px>\d+),(?P<py>\d+) v=(?P<vx>-?\d+),(?P<vy>-?\d+)$").unwrap();
+
+        let record = record(input, lines[0], &robot, "a robot").unwrap();
+        assert_eq!(record.get::<i32>("vy").unwrap(), -3);
+        assert_eq!(record.str("px"), "0");
+        assert!(record.get::<u8>("vy").is_err());
+
+        let err = super::record(input, lines[1], &robot, "a robot")
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "parse error at line 2, column 1: expected a robot"
+        );
+    }
+}
@@ -0,0 +1,330 @@
+use crate::{
+    error::AocError,
+    grid::{Grid, Pos},
+};
+use std::{fmt::Write as _, fs, io, path::Path};
+
+/// A 24-bit colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const GREY: Rgb = Rgb(128, 128, 128);
+    pub const SILVER: Rgb = Rgb(192, 192, 192);
+    pub const RED: Rgb = Rgb(220, 40, 40);
+    pub const GREEN: Rgb = Rgb(40, 180, 70);
+    pub const BLUE: Rgb = Rgb(50, 100, 200);
+    pub const GOLD: Rgb = Rgb(220, 170, 30);
+
+    /// A shade of grey, from white at 0 to black at 1.
+    pub fn shade(darkness: f32) -> Rgb {
+        let level = 255 - (darkness.clamp(0.0, 1.0) * 255.0) as u8;
+        Rgb(level, level, level)
+    }
+}
+
+/// What a cell shows, which decides how prominently it is drawn. Image
+/// backends leave background cells as blank canvas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Layer {
+    Background,
+    Terrain,
+    Entity,
+    Highlight,
+}
+
+impl Layer {
+    fn color(self) -> Rgb {
+        match self {
+            Layer::Background => Rgb::GREY,
+            Layer::Terrain => Rgb::SILVER,
+            Layer::Entity => Rgb::BLUE,
+            Layer::Highlight => Rgb::RED,
+        }
+    }
+}
+
+/// How one cell is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    pub color: Rgb,
+    pub layer: Layer,
+}
+
+impl Cell {
+    /// A cell in its layer's colour.
+    pub fn new(glyph: char, layer: Layer) -> Self {
+        Cell {
+            glyph,
+            color: layer.color(),
+            layer,
+        }
+    }
+
+    pub fn with_color(self, color: Rgb) -> Self {
+        Cell { color, ..self }
+    }
+}
+
+/// Something drawn as a rectangle of cells, such as a day's map.
+pub trait Render {
+    /// Width and height in cells.
+    fn size(&self) -> (usize, usize);
+
+    /// How the cell at `pos`, which is within `size`, is drawn.
+    fn cell(&self, pos: Pos) -> Cell;
+}
+
+impl<R: Render + ?Sized> Render for &R {
+    fn size(&self) -> (usize, usize) {
+        (**self).size()
+    }
+
+    fn cell(&self, pos: Pos) -> Cell {
+        (**self).cell(pos)
+    }
+}
+
+// Any character map, with `.` as open ground
+impl Render for Grid<char> {
+    fn size(&self) -> (usize, usize) {
+        (self.width(), self.height())
+    }
+
+    fn cell(&self, pos: Pos) -> Cell {
+        match self[pos] {
+            '.' => Cell::new('.', Layer::Background),
+            c => Cell::new(c, Layer::Terrain),
+        }
+    }
+}
+
+/// The part of a rendering to draw.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Viewport {
+    /// The square reaching `radius` cells out from `center`.
+    pub fn around((x, y): Pos, radius: usize) -> Self {
+        Viewport {
+            x: x.saturating_sub(radius),
+            y: y.saturating_sub(radius),
+            width: radius * 2 + 1,
+            height: radius * 2 + 1,
+        }
+    }
+
+    // Rows of positions in the viewport, clipped to `render`
+    pub(crate) fn rows(view: Option<Viewport>, render: &dyn Render) -> Vec<Vec<Pos>> {
+        let (width, height) = render.size();
+        let view = view.unwrap_or(Viewport {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        });
+        let xs = view.x.min(width)..(view.x + view.width).min(width);
+        let ys = view.y.min(height)..(view.y + view.height).min(height);
+        ys.map(|y| xs.clone().map(|x| (x, y)).collect()).collect()
+    }
+}
+
+/// Plain glyphs, a line per row.
+pub fn text(render: &dyn Render, view: Option<Viewport>) -> String {
+    let mut out = String::new();
+    for row in Viewport::rows(view, render) {
+        out.extend(row.into_iter().map(|pos| render.cell(pos).glyph));
+        out.push('\n');
+    }
+    out
+}
+
+/// Glyphs coloured with ANSI escapes, for a terminal.
+pub fn ansi(render: &dyn Render, view: Option<Viewport>) -> String {
+    let mut out = String::new();
+    for row in Viewport::rows(view, render) {
+        let mut current = None;
+        for pos in row {
+            let cell = render.cell(pos);
+            if current != Some(cell.color) {
+                let Rgb(r, g, b) = cell.color;
+                let _ = write!(out, "\x1b[38;2;{r};{g};{b}m");
+                current = Some(cell.color);
+            }
+            out.push(cell.glyph);
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+/// An SVG image with a `cell_size` pixel square per cell, grouped by layer.
+pub fn svg(render: &dyn Render, view: Option<Viewport>, cell_size: usize) -> String {
+    let rows = Viewport::rows(view, render);
+    let width = rows.first().map_or(0, Vec::len) * cell_size;
+    let height = rows.len() * cell_size;
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" style="background-color: white">"#
+    );
+    svg.push_str(&svg_layers(render, view, cell_size));
+    svg.push_str("</svg>");
+    svg
+}
+
+// The `<g>` of cells in each layer, without the surrounding `<svg>`
+pub(crate) fn svg_layers(render: &dyn Render, view: Option<Viewport>, cell_size: usize) -> String {
+    let mut cells: Vec<(usize, usize, Cell)> = Viewport::rows(view, render)
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, &pos)| (x, y, render.cell(pos)))
+        })
+        .filter(|(_, _, cell)| cell.layer != Layer::Background)
+        .collect();
+    cells.sort_by_key(|(_, _, cell)| cell.layer);
+
+    let mut svg = String::new();
+    let mut layer = None;
+    for (x, y, cell) in cells {
+        if layer != Some(cell.layer) {
+            if layer.is_some() {
+                svg.push_str("</g>");
+            }
+            let _ = write!(svg, r#"<g class="{:?}">"#, cell.layer);
+            layer = Some(cell.layer);
+        }
+        let Rgb(r, g, b) = cell.color;
+        let _ = write!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{cell_size}" height="{cell_size}" fill="rgb({r},{g},{b})" />"#,
+            x * cell_size,
+            y * cell_size,
+        );
+    }
+    if layer.is_some() {
+        svg.push_str("</g>");
+    }
+    svg
+}
+
+// RGB pixels, `scale` to a cell, and the image's width and height
+pub(crate) fn pixels(
+    render: &dyn Render,
+    view: Option<Viewport>,
+    scale: usize,
+) -> (Vec<u8>, usize, usize) {
+    let rows = Viewport::rows(view, render);
+    let width = rows.first().map_or(0, Vec::len) * scale;
+    let height = rows.len() * scale;
+
+    let mut pixels = Vec::with_capacity(width * height * 3);
+    for row in &rows {
+        let line: Vec<u8> = row
+            .iter()
+            .flat_map(|&pos| {
+                let cell = render.cell(pos);
+                let Rgb(r, g, b) = match cell.layer {
+                    Layer::Background => Rgb::WHITE,
+                    _ => cell.color,
+                };
+                [r, g, b].repeat(scale)
+            })
+            .collect();
+        for _ in 0..scale {
+            pixels.extend_from_slice(&line);
+        }
+    }
+    (pixels, width, height)
+}
+
+/// A binary PPM image with a `scale` pixel square per cell.
+pub fn ppm(render: &dyn Render, view: Option<Viewport>, scale: usize) -> Vec<u8> {
+    let (pixels, width, height) = pixels(render, view, scale);
+    let mut ppm = format!("P6\n{width} {height}\n255\n").into_bytes();
+    ppm.extend(pixels);
+    ppm
+}
+
+/// A PNG image with a `scale` pixel square per cell.
+pub fn png(render: &dyn Render, view: Option<Viewport>, scale: usize) -> Result<Vec<u8>, AocError> {
+    let (pixels, width, height) = pixels(render, view, scale);
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels))
+        .map_err(io::Error::other)?;
+    Ok(png)
+}
+
+/// Saves to `path` in the format its extension names: `svg`, `png`, `ppm`,
+/// or plain text otherwise.
+pub fn save(render: &dyn Render, view: Option<Viewport>, path: &Path) -> Result<(), AocError> {
+    let contents = match path.extension().and_then(|e| e.to_str()) {
+        Some("svg") => svg(render, view, 4).into_bytes(),
+        Some("png") => png(render, view, 4)?,
+        Some("ppm") => ppm(render, view, 4),
+        _ => text(render, view).into_bytes(),
+    };
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Checkers;
+
+    impl Render for Checkers {
+        fn size(&self) -> (usize, usize) {
+            (3, 2)
+        }
+
+        fn cell(&self, (x, y): Pos) -> Cell {
+            if (x + y) % 2 == 0 {
+                Cell::new('#', Layer::Terrain)
+            } else {
+                Cell::new('.', Layer::Background)
+            }
+        }
+    }
+
+    #[test]
+    fn backends() {
+        assert_eq!(text(&Checkers, None), "#.#\n.#.\n");
+        assert_eq!(text(&Checkers, Some(Viewport::around((2, 1), 0))), ".\n");
+        assert_eq!(
+            text(&Checkers, Some(Viewport::around((2, 0), 1))),
+            ".#\n#.\n"
+        );
+        assert_eq!(
+            ansi(&Checkers, Some(Viewport::around((0, 0), 0))),
+            "\x1b[38;2;192;192;192m#\x1b[0m\n"
+        );
+
+        let svg = svg(&Checkers, None, 2);
+        assert!(svg.contains(r#"width="6" height="4""#));
+        assert_eq!(svg.matches("<rect").count(), 3);
+
+        let ppm = ppm(&Checkers, None, 2);
+        assert!(ppm.starts_with(b"P6\n6 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 6 * 4 * 3);
+        assert_eq!(ppm[11..14], [192, 192, 192]);
+        assert_eq!(ppm[17..20], [255, 255, 255]);
+
+        assert!(png(&Checkers, None, 1).unwrap().starts_with(b"\x89PNG"));
+    }
+}
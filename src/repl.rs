@@ -0,0 +1,424 @@
+use crate::{
+    cli::ReplArgs,
+    params,
+    runner::{self, default_input, find_solution, part_label},
+    solution::DynSolution,
+    solutions, time_it, trace,
+};
+use rustyline::{
+    completion::{Completer, FilenameCompleter, Pair},
+    error::ReadlineError,
+    highlight::Highlighter,
+    hint::Hinter,
+    history::DefaultHistory,
+    validate::Validator,
+    Context, Editor, Helper,
+};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs,
+    io::{self, Write},
+};
+use tracing::level_filters::LevelFilter;
+
+const COMMANDS: &[(&str, &str, &str)] = &[
+    (":day", "<day>", "switch day and load its input.txt"),
+    (":load", "<path>", "read the input from a file"),
+    (":paste", "", "read the input up to a line of :end"),
+    (":run", "[1|2]", "run both parts, or just one"),
+    (":set", "<name> <n>", "override a parameter"),
+    (":unset", "<name>", "use the parameter's default"),
+    (":params", "", "list the day's parameters"),
+    (":time", "", "toggle timing each part"),
+    (":trace", "[level]", "toggle parse details and trace events"),
+    (":help", "", "show this list"),
+    (":quit", "", "leave"),
+];
+
+pub enum Flow {
+    Continue,
+    Paste,
+    Quit,
+}
+
+#[derive(Default)]
+pub struct Session {
+    solution: Option<&'static dyn DynSolution>,
+    /// Where the input came from, and its text
+    input: Option<(String, String)>,
+    params: HashMap<String, i64>,
+    time: bool,
+    /// The level of the day's trace events to show, when tracing
+    trace: Option<String>,
+}
+
+// Accepts `7`, `07`, `day7` and `day07`
+fn parse_day(s: &str) -> Result<u8, String> {
+    s.strip_prefix("day")
+        .unwrap_or(s)
+        .parse()
+        .map_err(|_| format!("`{s}` is not a day"))
+}
+
+fn day_name(day: u8) -> String {
+    format!("day{day:02}")
+}
+
+impl Session {
+    pub fn day(&self) -> Option<u8> {
+        self.solution.map(|s| s.day())
+    }
+
+    pub fn prompt(&self) -> String {
+        match self.day() {
+            Some(day) => format!("{}> ", day_name(day)),
+            None => "aoc> ".to_string(),
+        }
+    }
+
+    pub fn select(&mut self, day: u8, out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+        let solution = find_solution(day).ok_or(format!("day {day} has no solution"))?;
+        self.solution = Some(solution);
+        self.params.clear();
+        self.input = None;
+        writeln!(out, "Day {day}: {}", solution.title())?;
+
+        let path = default_input(day);
+        if path.is_file() {
+            self.load(&path.display().to_string(), out)?;
+        }
+        Ok(())
+    }
+
+    fn load(&mut self, path: &str, out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+        self.set_input(path.to_string(), text, out)
+    }
+
+    pub fn set_input(
+        &mut self,
+        source: String,
+        text: String,
+        out: &mut impl Write,
+    ) -> Result<(), Box<dyn Error>> {
+        writeln!(out, "Input: {source}, {} lines", text.lines().count())?;
+        self.input = Some((source, text));
+        Ok(())
+    }
+
+    fn solution(&self) -> Result<&'static dyn DynSolution, String> {
+        self.solution
+            .ok_or("pick a day first with :day".to_string())
+    }
+
+    fn run(&self, only: Option<u8>, out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+        let solution = self.solution()?;
+        match &self.trace {
+            Some(level) => {
+                let spec = format!("{}={level}", day_name(solution.day()));
+                let subscriber = trace::subscriber(&[spec])?;
+                tracing::subscriber::with_default(subscriber, || self.solve(solution, only, out))
+            }
+            None => self.solve(solution, only, out),
+        }
+    }
+
+    fn solve(
+        &self,
+        solution: &'static dyn DynSolution,
+        only: Option<u8>,
+        out: &mut impl Write,
+    ) -> Result<(), Box<dyn Error>> {
+        let (source, text) = self
+            .input
+            .as_ref()
+            .ok_or("no input yet, use :load or :paste")?;
+
+        let (input, parse) = time_it!(runner::catch_quietly(|| solution.parse(text)));
+        let input = input??;
+        if self.trace.is_some() {
+            writeln!(out, "  parsed {source} in {:.1?}", parse)?;
+        }
+
+        for (part, solved) in [(1, true), (2, solution.has_part_two())] {
+            if !solved || only.is_some_and(|only| only != part) {
+                continue;
+            }
+            let label = part_label(solution.day(), part);
+            let ((result, read), duration) = time_it!(params::with(&self.params, || {
+                runner::catch_quietly(|| match part {
+                    1 => solution.part_one(input.as_ref()),
+                    _ => solution.part_two(input.as_ref()),
+                })
+            }));
+            if self.trace.is_some() {
+                for (name, value) in read {
+                    writeln!(out, "  {label} read {name} = {value}")?;
+                }
+            }
+            let timing = if self.time {
+                format!(" in {:.1?}", duration)
+            } else {
+                String::new()
+            };
+            match result {
+                Ok(Ok(answer)) => writeln!(out, "{label}{timing} -> {answer}")?,
+                Ok(Err(e)) => writeln!(out, "{label}{timing} failed: {e}")?,
+                Err(message) => writeln!(out, "{label}{timing} panicked: {message}")?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn list_params(&self, out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+        let solution = self.solution()?;
+        if solution.params().is_empty() {
+            writeln!(out, "Day {} has no parameters", solution.day())?;
+        }
+        for param in solution.params() {
+            match self.params.get(param.name) {
+                Some(value) => writeln!(out, "{} = {value}: {}", param.name, param.about)?,
+                None => writeln!(out, "{}: {}", param.name, param.about)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn check_param(&self, name: &str) -> Result<(), String> {
+        let solution = self.solution()?;
+        if solution.params().iter().any(|p| p.name == name) {
+            Ok(())
+        } else {
+            Err(format!("day {} has no parameter `{name}`", solution.day()))
+        }
+    }
+
+    pub fn handle(&mut self, line: &str, out: &mut impl Write) -> Result<Flow, Box<dyn Error>> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [] => {}
+            [":day", day] => self.select(parse_day(day)?, out)?,
+            [":load", path] => self.load(path, out)?,
+            [":paste"] => return Ok(Flow::Paste),
+            [":run"] => self.run(None, out)?,
+            [":run", part @ ("1" | "2")] => self.run(part.parse().ok(), out)?,
+            [":set", name, value] => {
+                self.check_param(name)?;
+                let value = value
+                    .parse()
+                    .map_err(|_| format!("`{value}` is not a number"))?;
+                self.params.insert(name.to_string(), value);
+            }
+            [":unset", name] => {
+                self.check_param(name)?;
+                self.params.remove(*name);
+            }
+            [":params"] => self.list_params(out)?,
+            [":time"] => {
+                self.time = !self.time;
+                writeln!(out, "Timing {}", if self.time { "on" } else { "off" })?;
+            }
+            [":trace"] => {
+                self.trace = match self.trace {
+                    Some(_) => None,
+                    None => Some("debug".to_string()),
+                };
+                writeln!(
+                    out,
+                    "Tracing {}",
+                    if self.trace.is_some() { "on" } else { "off" }
+                )?;
+            }
+            [":trace", level] => {
+                level
+                    .parse::<LevelFilter>()
+                    .map_err(|_| format!("`{level}` is not a trace level"))?;
+                self.trace = Some(level.to_string());
+                writeln!(out, "Tracing at {level}")?;
+            }
+            [":help"] => {
+                for (command, args, about) in COMMANDS {
+                    writeln!(out, "{:<18}{about}", format!("{command} {args}"))?;
+                }
+            }
+            [":quit"] => return Ok(Flow::Quit),
+            _ => return Err(format!("unknown command `{line}`, try :help").into()),
+        }
+        Ok(Flow::Continue)
+    }
+}
+
+/// Candidates for the word before the cursor, and where that word starts.
+pub fn complete(line: &str, day: Option<u8>) -> (usize, Vec<String>) {
+    let start = line.rfind(' ').map_or(0, |i| i + 1);
+    let word = &line[start..];
+    let words: Vec<&str> = line[..start].split_whitespace().collect();
+
+    let candidates: Vec<String> = match words.as_slice() {
+        [] => COMMANDS
+            .iter()
+            .map(|(command, ..)| command.to_string())
+            .collect(),
+        [":day"] => solutions().iter().map(|s| day_name(s.day())).collect(),
+        [":set" | ":unset"] => day
+            .and_then(find_solution)
+            .map(|s| s.params().iter().map(|p| p.name.to_string()).collect())
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+
+    (
+        start,
+        candidates
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(word))
+            .collect(),
+    )
+}
+
+struct ReplHelper {
+    day: Option<u8>,
+    files: FilenameCompleter,
+}
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        if line.starts_with(":load ") {
+            return self.files.complete(line, pos, ctx);
+        }
+        let (start, candidates) = complete(&line[..pos], self.day);
+        let pairs = candidates
+            .into_iter()
+            .map(|candidate| Pair {
+                display: candidate.clone(),
+                replacement: candidate,
+            })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+pub fn repl(args: &ReplArgs) -> Result<(), Box<dyn Error>> {
+    let mut editor: Editor<ReplHelper, DefaultHistory> = Editor::new()?;
+    editor.set_helper(Some(ReplHelper {
+        day: None,
+        files: FilenameCompleter::new(),
+    }));
+    // There is no history the first time round
+    let _ = editor.load_history(&args.history);
+
+    let mut session = Session::default();
+    let mut stdout = io::stdout();
+    if let Some(day) = args.day {
+        session.select(day, &mut stdout)?;
+    }
+
+    loop {
+        if let Some(helper) = editor.helper_mut() {
+            helper.day = session.day();
+        }
+        let line = match editor.readline(&session.prompt()) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line.as_str())?;
+
+        match session.handle(line.trim(), &mut stdout) {
+            Ok(Flow::Continue) => {}
+            Ok(Flow::Paste) => {
+                let mut text = String::new();
+                loop {
+                    match editor.readline("... ") {
+                        Ok(line) if line.trim() == ":end" => break,
+                        Ok(line) => {
+                            text.push_str(&line);
+                            text.push('\n');
+                        }
+                        Err(ReadlineError::Eof) => break,
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+                session.set_input("pasted text".to_string(), text, &mut stdout)?;
+            }
+            Ok(Flow::Quit) => break,
+            Err(e) => println!("error: {e}"),
+        }
+    }
+
+    editor.save_history(&args.history)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(session: &mut Session, line: &str) -> String {
+        let mut out = Vec::new();
+        session.handle(line, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn overrides_params() {
+        let mut session = Session::default();
+        run(&mut session, ":day day11");
+        session
+            .set_input(
+                "stones".to_string(),
+                "125 17\n".to_string(),
+                &mut Vec::new(),
+            )
+            .unwrap();
+
+        run(&mut session, ":set blinks 6");
+        assert_eq!(run(&mut session, ":run 1"), "day11::part_one -> 22\n");
+        run(&mut session, ":trace");
+        let traced = run(&mut session, ":run 2");
+        assert!(traced.starts_with("  parsed stones in "));
+        assert!(traced.ends_with("  day11::part_two read blinks = 6\nday11::part_two -> 22\n"));
+
+        run(&mut session, ":trace");
+        run(&mut session, ":unset blinks");
+        assert_eq!(run(&mut session, ":run 1"), "day11::part_one -> 55312\n");
+        assert!(session.handle(":set seconds 5", &mut Vec::new()).is_err());
+        assert!(session.handle(":bogus", &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn completes() {
+        assert_eq!(complete(":r", None), (0, vec![":run".to_string()]));
+        let (start, days) = complete(":day day1", None);
+        assert_eq!(start, 5);
+        assert!(days.contains(&"day11".to_string()) && !days.contains(&"day09".to_string()));
+        assert_eq!(
+            complete(":set b", Some(11)),
+            (5, vec!["blinks".to_string()])
+        );
+        assert_eq!(complete(":set b", None), (5, Vec::new()));
+    }
+}
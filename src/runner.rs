@@ -0,0 +1,613 @@
+use crate::{
+    answers::{Answers, Verdict},
+    cli::{Format, RunArgs, Selection},
+    deadline,
+    error::AocError,
+    history,
+    memory::{self, AllocStats},
+    output,
+    solution::{Answer, DynSolution},
+    solutions, time_it,
+};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    any::Any,
+    cell::Cell,
+    error::Error,
+    fmt, fs, io,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{Once, OnceLock},
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, PartialEq)]
+pub enum RunError {
+    UnknownDay(u8),
+    MissingPart { day: u8, part: u8 },
+    MissingInput(PathBuf),
+    InputForManyDays,
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RunError::UnknownDay(day) => write!(f, "day {day} has no solution"),
+            RunError::MissingPart { day, part } => write!(f, "day {day} has no part {part}"),
+            RunError::MissingInput(path) => {
+                write!(f, "input file {} does not exist", path.display())
+            }
+            RunError::InputForManyDays => write!(f, "--input can only be used with a single day"),
+        }
+    }
+}
+
+impl Error for RunError {}
+
+pub struct Part {
+    pub solution: &'static dyn DynSolution,
+    pub part: u8,
+    pub input: PathBuf,
+    pub timeout: Option<Duration>,
+}
+
+impl Part {
+    pub fn label(&self) -> String {
+        part_label(self.solution.day(), self.part)
+    }
+
+    /// Solves this part, stopping at its first cancellation point once the
+    /// timeout has passed.
+    pub fn solve(&self, input: &dyn Any) -> Result<Answer, AocError> {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        deadline::with_deadline(deadline, || match self.part {
+            1 => self.solution.part_one(input),
+            _ => self.solution.part_two(input),
+        })
+    }
+
+    /// Parses `input` and solves this part from it.
+    pub fn run(&self, input: &str) -> Result<Answer, AocError> {
+        let input = self.solution.parse(input)?;
+        self.solve(input.as_ref())
+    }
+}
+
+pub fn part_label(day: u8, part: u8) -> String {
+    let part = if part == 1 { "part_one" } else { "part_two" };
+    format!("day{day:02}::{part}")
+}
+
+pub fn find_solution(day: u8) -> Option<&'static dyn DynSolution> {
+    solutions().iter().copied().find(|s| s.day() == day)
+}
+
+pub fn default_input(day: u8) -> PathBuf {
+    PathBuf::from(format!("src/day{day:02}/input.txt"))
+}
+
+pub fn is_stdin(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+// Stdin can only be read once, so every part given `-` shares the first read
+pub fn read_input(path: &Path) -> io::Result<String> {
+    static STDIN: OnceLock<Result<String, String>> = OnceLock::new();
+
+    if is_stdin(path) {
+        STDIN
+            .get_or_init(|| io::read_to_string(io::stdin()).map_err(|e| e.to_string()))
+            .clone()
+            .map_err(io::Error::other)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+// Resolve the requested days, parts and inputs up front so a bad request
+// fails before anything has been run
+pub fn plan(args: &Selection) -> Result<Vec<Part>, RunError> {
+    let selected: Vec<&'static dyn DynSolution> = match &args.days {
+        Some(range) if !args.all => range
+            .clone()
+            .map(|n| find_solution(n).ok_or(RunError::UnknownDay(n)))
+            .collect::<Result<_, _>>()?,
+        _ => solutions().to_vec(),
+    };
+
+    if args.input.is_some() && selected.len() > 1 {
+        return Err(RunError::InputForManyDays);
+    }
+
+    let mut plan = Vec::new();
+    for solution in selected {
+        let input = args
+            .input
+            .clone()
+            .unwrap_or_else(|| default_input(solution.day()));
+        for (part, solved) in [(1, true), (2, solution.has_part_two())] {
+            if args.part.is_some_and(|p| p != part) {
+                continue;
+            }
+            if solved {
+                plan.push(Part {
+                    solution,
+                    part,
+                    input: input.clone(),
+                    timeout: args.timeout.or(solution.timeout()),
+                });
+            } else if args.part.is_some() {
+                return Err(RunError::MissingPart {
+                    day: solution.day(),
+                    part,
+                });
+            }
+        }
+    }
+
+    Ok(plan)
+}
+
+pub fn check_inputs(plan: &[Part]) -> Result<(), RunError> {
+    match plan
+        .iter()
+        .find(|part| !is_stdin(&part.input) && !part.input.is_file())
+    {
+        Some(part) => Err(RunError::MissingInput(part.input.clone())),
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Ok,
+    Failed,
+    Missing,
+    TimedOut,
+}
+
+/// The outcome of running one part, as reported by every output format.
+#[derive(Debug, Serialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub title: &'static str,
+    pub status: Status,
+    pub answer: Option<String>,
+    pub answer_type: Option<&'static str>,
+    /// Time spent solving, after the input was parsed.
+    pub duration_ms: Option<f64>,
+    /// Time spent parsing the input, which the day's parts share.
+    pub parse_ms: Option<f64>,
+    pub alloc: Option<AllocStats>,
+    pub input: PathBuf,
+    pub message: Option<String>,
+    pub verdict: Option<Verdict>,
+    pub expected: Option<String>,
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+// The hook is process wide, so rather than swapping it around every part,
+// which races with other threads doing the same, one hook is installed for
+// good and stays silent only on threads inside `catch_quietly`
+fn install_quiet_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.get() {
+                previous(info);
+            }
+        }));
+    });
+}
+
+/// Runs `f` on its own, turning a panic into its message without printing it.
+pub fn catch_quietly<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    install_quiet_hook();
+    let quiet = QUIET.replace(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(f)).map_err(panic_message);
+    QUIET.set(quiet);
+    result
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Runs one part on its own.
+pub fn execute(part: &Part) -> Record {
+    execute_day(std::slice::from_ref(part)).remove(0)
+}
+
+/// Runs parts of one day that share an input, which is read and parsed once
+/// for all of them. Parsing is timed apart from each part's solve.
+pub fn execute_day(parts: &[Part]) -> Vec<Record> {
+    let mut records: Vec<Record> = parts
+        .iter()
+        .map(|part| Record {
+            day: part.solution.day(),
+            part: part.part,
+            title: part.solution.title(),
+            status: Status::Missing,
+            answer: None,
+            answer_type: None,
+            duration_ms: None,
+            parse_ms: None,
+            alloc: None,
+            input: part.input.clone(),
+            message: None,
+            verdict: None,
+            expected: None,
+        })
+        .collect();
+    let Some(first) = parts.first() else {
+        return records;
+    };
+
+    let content = match read_input(&first.input) {
+        Ok(content) => content,
+        Err(e) => {
+            for record in &mut records {
+                record.message = Some(e.to_string());
+            }
+            return records;
+        }
+    };
+
+    let (input, parse) = time_it!(catch_quietly(|| first.solution.parse(&content)));
+    let input = match input {
+        Ok(Ok(input)) => Ok(input),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(message) => Err(message),
+    };
+    let input = match input {
+        Ok(input) => input,
+        Err(message) => {
+            for record in &mut records {
+                record.status = Status::Failed;
+                record.parse_ms = Some(ms(parse));
+                record.message = Some(message.clone());
+            }
+            return records;
+        }
+    };
+
+    for (part, record) in parts.iter().zip(&mut records) {
+        let ((result, duration), alloc) =
+            memory::measure(|| time_it!(catch_quietly(|| part.solve(input.as_ref()))));
+
+        record.parse_ms = Some(ms(parse));
+        record.duration_ms = Some(ms(duration));
+        record.alloc = alloc;
+        match result {
+            Ok(Ok(answer)) => {
+                record.status = Status::Ok;
+                record.answer = Some(answer.to_string());
+                record.answer_type = Some(answer.type_name());
+            }
+            Ok(Err(AocError::TimedOut)) => {
+                record.status = Status::TimedOut;
+                record.message = Some(format!(
+                    "timed out after {}s",
+                    part.timeout.unwrap_or_default().as_secs_f64()
+                ));
+            }
+            Ok(Err(e)) => {
+                record.status = Status::Failed;
+                record.message = Some(e.to_string());
+            }
+            Err(message) => {
+                record.status = Status::Failed;
+                record.message = Some(message);
+            }
+        }
+    }
+
+    records
+}
+
+// A panicking part is reported through its record, so parts run quietly on
+// whichever thread they land on. Allocation counts are process wide, so they
+// are dropped when days overlap.
+pub fn execute_all(plan: &[Part], parallel: bool) -> Vec<Record> {
+    // the plan lists a day's parts next to each other
+    let days: Vec<&[Part]> = plan
+        .chunk_by(|a, b| a.solution.day() == b.solution.day() && a.input == b.input)
+        .collect();
+    let records = if parallel {
+        days.par_iter()
+            .flat_map_iter(|parts| execute_day(parts))
+            .map(|record| Record {
+                alloc: None,
+                ..record
+            })
+            .collect()
+    } else {
+        days.into_iter().flat_map(execute_day).collect()
+    };
+    records
+}
+
+// Answers are only known for the real inputs, so parts run against any
+// other file are left unchecked
+pub fn check_answers(records: &mut [Record], answers: &Answers) {
+    for record in records {
+        let Some(answer) = &record.answer else {
+            continue;
+        };
+        if record.input != default_input(record.day) {
+            continue;
+        }
+        let verdict = answers.check(record.day, record.part, answer);
+        record.expected = match verdict {
+            Verdict::Fail => answers.get(record.day, record.part).map(str::to_string),
+            _ => None,
+        };
+        record.verdict = Some(verdict);
+    }
+}
+
+// Returns how many answers were added
+pub fn record_answers(records: &[Record], answers: &mut Answers) -> usize {
+    let mut added = 0;
+    for record in records {
+        if record.verdict == Some(Verdict::Unknown) {
+            if let Some(answer) = &record.answer {
+                answers.set(record.day, record.part, answer.clone());
+                added += 1;
+            }
+        }
+    }
+    added
+}
+
+pub fn run(args: &RunArgs) -> Result<(), Box<dyn Error>> {
+    let plan = plan(&args.selection)?;
+
+    // Structured output reports missing inputs per part, text fails up front
+    if args.format == Format::Text {
+        check_inputs(&plan)?;
+    }
+
+    let mut answers = Answers::load(&args.answers)?;
+    let (mut records, wall) = time_it!(execute_all(&plan, args.parallel));
+    check_answers(&mut records, &answers);
+
+    let mut stdout = io::stdout().lock();
+    output::write(&mut stdout, args.format, &records)?;
+    if args.format == Format::Text {
+        output::write_totals(&mut stdout, &records, wall)?;
+    }
+    // parts timed while competing for every core would skew the baseline
+    if !args.parallel && !args.no_history {
+        history::append(&args.history, &records)?;
+    }
+
+    if args.record && record_answers(&records, &mut answers) > 0 {
+        answers.save(&args.answers)?;
+    }
+
+    let failed = records.iter().filter(|r| r.status != Status::Ok).count();
+    if failed > 0 {
+        return Err(format!("{failed} part(s) did not produce an answer").into());
+    }
+
+    let wrong = records
+        .iter()
+        .filter(|r| r.verdict == Some(Verdict::Fail))
+        .count();
+    if wrong > 0 {
+        return Err(format!("{wrong} answer(s) differ from {}", args.answers.display()).into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(days: Option<std::ops::RangeInclusive<u8>>, part: Option<u8>) -> Selection {
+        Selection {
+            days,
+            all: false,
+            part,
+            input: Some(PathBuf::from("src/day15/smalltest.txt")),
+            timeout: None,
+        }
+    }
+
+    #[test]
+    fn rejects_bad_requests() {
+        assert_eq!(
+            plan(&args(Some(26..=26), None)).err(),
+            Some(RunError::UnknownDay(26))
+        );
+        assert_eq!(
+            plan(&args(Some(15..=15), Some(2))).err(),
+            Some(RunError::MissingPart { day: 15, part: 2 })
+        );
+        assert_eq!(
+            plan(&args(Some(1..=2), None)).err(),
+            Some(RunError::InputForManyDays)
+        );
+
+        let mut missing = args(Some(1..=1), None);
+        missing.input = Some(PathBuf::from("src/day01/nope.txt"));
+        assert_eq!(
+            check_inputs(&plan(&missing).unwrap()).err(),
+            Some(RunError::MissingInput(PathBuf::from("src/day01/nope.txt")))
+        );
+    }
+
+    #[test]
+    fn records_outcomes() {
+        let part = |input: &str| Part {
+            solution: find_solution(1).unwrap(),
+            part: 1,
+            input: PathBuf::from(input),
+            timeout: None,
+        };
+
+        let plan = [
+            part("src/day01/test.txt"),
+            part("src/day03/test.txt"),
+            part("src/day01/nope.txt"),
+        ];
+        let records = execute_all(&plan, false);
+
+        assert_eq!(records[0].status, Status::Ok);
+        assert_eq!(records[0].answer.as_deref(), Some("11"));
+        assert_eq!(records[0].answer_type, Some("i32"));
+        assert_eq!(records[1].status, Status::Failed);
+        assert!(records[1].message.is_some());
+        assert_eq!(records[2].status, Status::Missing);
+        assert_eq!(records[2].duration_ms, None);
+        assert_eq!(records[2].parse_ms, None);
+
+        // both parts are solved from a single parse
+        let both = execute_day(&[
+            part("src/day01/test.txt"),
+            Part {
+                part: 2,
+                ..part("src/day01/test.txt")
+            },
+        ]);
+        assert_eq!(both[1].answer.as_deref(), Some("31"));
+        assert!(both[0].parse_ms.is_some());
+        assert_eq!(both[0].parse_ms, both[1].parse_ms);
+
+        let parallel = execute_all(&plan, true);
+        let statuses = |records: &[Record]| records.iter().map(|r| r.status).collect::<Vec<_>>();
+        assert_eq!(statuses(&parallel), statuses(&records));
+        assert_eq!(parallel[0].answer.as_deref(), Some("11"));
+    }
+
+    #[test]
+    fn checks_answers() {
+        let record = |input: PathBuf, answer: &str| Record {
+            day: 1,
+            part: 1,
+            title: "Basic list sorting",
+            status: Status::Ok,
+            answer: Some(answer.to_string()),
+            answer_type: Some("i32"),
+            duration_ms: Some(1.0),
+            parse_ms: Some(0.5),
+            alloc: None,
+            input,
+            message: None,
+            verdict: None,
+            expected: None,
+        };
+        let mut answers = Answers::default();
+        answers.set(1, 1, "11".to_string());
+
+        let mut records = [
+            record(default_input(1), "11"),
+            record(default_input(1), "12"),
+            record(PathBuf::from("src/day01/test.txt"), "12"),
+        ];
+        check_answers(&mut records, &answers);
+        assert_eq!(records[0].verdict, Some(Verdict::Pass));
+        assert_eq!(records[1].verdict, Some(Verdict::Fail));
+        assert_eq!(records[1].expected.as_deref(), Some("11"));
+        assert_eq!(records[2].verdict, None);
+
+        let mut answers = Answers::default();
+        check_answers(&mut records, &answers);
+        assert_eq!(records[0].verdict, Some(Verdict::Unknown));
+        assert_eq!(record_answers(&records, &mut answers), 2);
+        assert_eq!(answers.get(1, 1), Some("12"));
+    }
+
+    #[test]
+    fn catches_panics_on_many_threads() {
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                std::thread::spawn(move || {
+                    let result = catch_quietly(|| {
+                        assert!(i % 2 == 1, "part {i} panicked");
+                        i
+                    });
+                    (result, QUIET.get())
+                })
+            })
+            .collect();
+        for (i, thread) in threads.into_iter().enumerate() {
+            let (result, quiet) = thread.join().unwrap();
+            match i % 2 {
+                0 => assert_eq!(result, Err(format!("part {i} panicked"))),
+                _ => assert_eq!(result, Ok(i)),
+            }
+            assert!(!quiet);
+        }
+    }
+
+    #[test]
+    fn times_out() {
+        let part = |timeout: Option<Duration>| Part {
+            solution: find_solution(6).unwrap(),
+            part: 2,
+            input: PathBuf::from("src/day06/test.txt"),
+            timeout,
+        };
+
+        let record = execute(&part(Some(Duration::ZERO)));
+        assert_eq!(record.status, Status::TimedOut);
+        assert_eq!(record.message.as_deref(), Some("timed out after 0s"));
+        assert_eq!(execute(&part(None)).answer.as_deref(), Some("6"));
+
+        let mut selection = args(Some(6..=6), Some(2));
+        assert_eq!(
+            plan(&selection).unwrap()[0].timeout,
+            Some(Duration::from_secs(60))
+        );
+        selection.timeout = Some(Duration::from_millis(500));
+        assert_eq!(
+            plan(&selection).unwrap()[0].timeout,
+            Some(Duration::from_millis(500))
+        );
+    }
+
+    #[test]
+    fn solves_text() {
+        let part = Part {
+            solution: find_solution(1).unwrap(),
+            part: 2,
+            input: PathBuf::from("-"),
+            timeout: None,
+        };
+        assert_eq!(
+            part.run("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n")
+                .unwrap(),
+            Answer::I32(31)
+        );
+    }
+
+    #[test]
+    fn selects_parts() {
+        assert_eq!(plan(&args(Some(15..=15), None)).unwrap().len(), 1);
+        assert_eq!(plan(&args(Some(7..=7), None)).unwrap().len(), 2);
+        assert_eq!(
+            plan(&args(Some(7..=7), Some(2))).unwrap()[0].label(),
+            "day07::part_two"
+        );
+    }
+}
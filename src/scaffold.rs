@@ -0,0 +1,133 @@
+use crate::cli::NewArgs;
+use std::{error::Error, fs, path::Path};
+
+const TEMPLATE: &str = include_str!("../templates/day.rs.tmpl");
+
+// Commented out until the answers are known, so no tests are generated yet
+const EXAMPLES: &str = "\
+# Each example becomes a test per part that has an answer
+# [[example]]
+# file = \"test.txt\"
+# part_one = 0
+# part_two = 0
+";
+
+pub fn render(day: u8, title: &str) -> String {
+    TEMPLATE
+        .replace("{{DAY}}", &format!("{day:02}"))
+        .replace("{{NUMBER}}", &day.to_string())
+        .replace(
+            "{{TITLE}}",
+            &title.replace('\\', "\\\\").replace('"', "\\\""),
+        )
+}
+
+/// Adds the day to the `solutions!` list in `lib.rs`, keeping it in day order.
+pub fn register(lib: &str, day: u8) -> Result<String, String> {
+    let start = lib
+        .find("solutions! {\n")
+        .ok_or("could not find the `solutions!` list")?
+        + "solutions! {\n".len();
+    let end = start
+        + lib[start..]
+            .find("}\n")
+            .ok_or("the `solutions!` list is not closed")?;
+
+    let entry = format!("    day{day:02}::Day{day:02},\n");
+    let mut entries: Vec<&str> = lib[start..end].split_inclusive('\n').collect();
+    if entries.contains(&entry.as_str()) {
+        return Err(format!("day {day} is already registered"));
+    }
+    entries.push(&entry);
+    entries.sort();
+
+    Ok(format!(
+        "{}{}{}",
+        &lib[..start],
+        entries.concat(),
+        &lib[end..]
+    ))
+}
+
+/// Creates `src/dayNN` under `root` and registers it, refusing to touch a
+/// day that already has a module.
+pub fn scaffold(root: &Path, day: u8, title: &str) -> Result<(), Box<dyn Error>> {
+    let dir = root.join(format!("src/day{day:02}"));
+    let module = dir.join("mod.rs");
+    if module.exists() {
+        return Err(format!("{} already exists", module.display()).into());
+    }
+
+    let lib_path = root.join("src/lib.rs");
+    let lib = register(&fs::read_to_string(&lib_path)?, day)?;
+
+    fs::create_dir_all(&dir)?;
+    fs::write(&module, render(day, title))?;
+    let example = dir.join("test.txt");
+    if !example.exists() {
+        fs::write(example, "")?;
+    }
+    fs::write(dir.join("examples.toml"), EXAMPLES)?;
+    fs::write(lib_path, lib)?;
+
+    Ok(())
+}
+
+pub fn new(args: &NewArgs) -> Result<(), Box<dyn Error>> {
+    scaffold(Path::new("."), args.day, &args.title)?;
+    println!(
+        "\tCreated src/day{:02}, paste the example into test.txt and its answers into examples.toml",
+        args.day
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    const LIB: &str =
+        "pub mod cli;\n\nsolutions! {\n    day01::Day01,\n    day15::Day15,\n}\n\nfn after() {}\n";
+
+    #[test]
+    fn registers_in_order() {
+        assert_eq!(
+            register(LIB, 7).unwrap(),
+            "pub mod cli;\n\nsolutions! {\n    day01::Day01,\n    day07::Day07,\n    day15::Day15,\n}\n\nfn after() {}\n"
+        );
+        assert!(register(LIB, 15).is_err());
+    }
+
+    #[test]
+    fn renders() {
+        let module = render(7, "Say \"hi\"");
+        assert!(module.contains("pub struct Day07;"));
+        assert!(module.contains("const DAY: u8 = 7;"));
+        assert!(module.contains(r#"const TITLE: &'static str = "Say \"hi\"";"#));
+        assert!(!module.contains("todo!"));
+    }
+
+    #[test]
+    fn refuses_to_overwrite() {
+        let root = env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+
+        scaffold(&root, 16, "Reindeer Maze").unwrap();
+        assert!(root.join("src/day16/test.txt").is_file());
+        assert!(root.join("src/day16/examples.toml").is_file());
+        assert!(fs::read_to_string(root.join("src/lib.rs"))
+            .unwrap()
+            .contains("    day16::Day16,\n}"));
+
+        let module = fs::read_to_string(root.join("src/day16/mod.rs")).unwrap();
+        assert!(scaffold(&root, 16, "Again").is_err());
+        assert_eq!(
+            fs::read_to_string(root.join("src/day16/mod.rs")).unwrap(),
+            module
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
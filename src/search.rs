@@ -0,0 +1,314 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+pub type Cost = u64;
+
+/// The cheapest cost to every node a search reached, with every neighbour
+/// each one can be reached from at that cost.
+#[derive(Debug, Clone)]
+pub struct Paths<N> {
+    start: N,
+    costs: HashMap<N, Cost>,
+    predecessors: HashMap<N, Vec<N>>,
+}
+
+impl<N: Clone + Eq + Hash> Paths<N> {
+    fn new(start: N) -> Self {
+        Paths {
+            costs: HashMap::from([(start.clone(), 0)]),
+            predecessors: HashMap::new(),
+            start,
+        }
+    }
+
+    // Records that `node` is reachable from `from` for `cost`, returning
+    // whether that is cheaper than before
+    fn relax(&mut self, node: &N, from: &N, cost: Cost) -> bool {
+        // nothing beats starting there, and a way back in would let paths
+        // run round in circles
+        if *node == self.start {
+            return false;
+        }
+        match self.costs.get(node) {
+            Some(&best) if cost > best => false,
+            Some(&best) if cost == best => {
+                self.predecessors
+                    .entry(node.clone())
+                    .or_default()
+                    .push(from.clone());
+                false
+            }
+            _ => {
+                self.costs.insert(node.clone(), cost);
+                self.predecessors.insert(node.clone(), vec![from.clone()]);
+                true
+            }
+        }
+    }
+
+    pub fn start(&self) -> &N {
+        &self.start
+    }
+
+    pub fn cost(&self, node: &N) -> Option<Cost> {
+        self.costs.get(node).copied()
+    }
+
+    /// Every node the search reached, the start included.
+    pub fn reached(&self) -> impl Iterator<Item = &N> {
+        self.costs.keys()
+    }
+
+    /// The nodes one step before `node` on its cheapest paths.
+    pub fn predecessors(&self, node: &N) -> &[N] {
+        self.predecessors.get(node).map_or(&[], Vec::as_slice)
+    }
+
+    /// One cheapest path from the start to `goal`, both ends included.
+    pub fn path_to(&self, goal: &N) -> Option<Vec<N>> {
+        self.costs.get(goal)?;
+        let mut path = vec![goal.clone()];
+        while *path.last()? != self.start {
+            path.push(self.predecessors(path.last()?).first()?.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// How many distinct cheapest paths lead from the start to `goal`.
+    pub fn count_paths(&self, goal: &N) -> u64 {
+        let mut counts = HashMap::new();
+        self.count_paths_memo(goal, &mut counts)
+    }
+
+    fn count_paths_memo(&self, node: &N, counts: &mut HashMap<N, u64>) -> u64 {
+        if *node == self.start {
+            return 1;
+        }
+        if let Some(&count) = counts.get(node) {
+            return count;
+        }
+        let count = self
+            .predecessors(node)
+            .iter()
+            .map(|previous| self.count_paths_memo(previous, counts))
+            .sum();
+        counts.insert(node.clone(), count);
+        count
+    }
+
+    /// Every node on at least one cheapest path to `goal`.
+    pub fn on_any_path(&self, goal: &N) -> HashSet<N> {
+        let mut seen = HashSet::new();
+        if self.costs.contains_key(goal) {
+            let mut stack = vec![goal.clone()];
+            while let Some(node) = stack.pop() {
+                if seen.insert(node.clone()) {
+                    stack.extend(self.predecessors(&node).iter().cloned());
+                }
+            }
+        }
+        seen
+    }
+}
+
+/// Breadth-first search, where every step costs one.
+pub fn bfs<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> Paths<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut paths = Paths::new(start.clone());
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((node, cost)) = queue.pop_front() {
+        for next in neighbors(&node) {
+            if paths.relax(&next, &node, cost + 1) {
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+
+    paths
+}
+
+/// Depth-first search, returning nodes in the order they are first visited.
+pub fn dfs<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> Vec<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = HashSet::new();
+    let mut order = Vec::new();
+    let mut stack = vec![start];
+
+    while let Some(node) = stack.pop() {
+        if !seen.insert(node.clone()) {
+            continue;
+        }
+        let mut next: Vec<N> = neighbors(&node)
+            .into_iter()
+            .filter(|next| !seen.contains(next))
+            .collect();
+        // so the first neighbour is visited first
+        next.reverse();
+        stack.extend(next);
+        order.push(node);
+    }
+
+    order
+}
+
+/// Every node reachable from `start`, `start` included.
+pub fn reachable<N, I>(start: N, neighbors: impl FnMut(&N) -> I) -> HashSet<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    dfs(start, neighbors).into_iter().collect()
+}
+
+/// Dijkstra's algorithm, over neighbours given with the cost of stepping
+/// to them.
+pub fn dijkstra<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> Paths<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, Cost)>,
+{
+    let mut paths = Paths::new(start.clone());
+    // the heap holds indices into `nodes`, so nodes needn't be `Ord`
+    let mut nodes = vec![start];
+    let mut queue = BinaryHeap::from([Reverse((0, 0))]);
+
+    while let Some(Reverse((cost, i))) = queue.pop() {
+        let node = nodes[i].clone();
+        if paths.cost(&node).is_some_and(|best| cost > best) {
+            continue;
+        }
+        for (next, step) in neighbors(&node) {
+            if paths.relax(&next, &node, cost + step) {
+                queue.push(Reverse((cost + step, nodes.len())));
+                nodes.push(next);
+            }
+        }
+    }
+
+    paths
+}
+
+/// A* search to the first node that `is_goal` accepts, returning the path
+/// there and its cost. `heuristic` must never overestimate the remaining
+/// cost.
+pub fn astar<N, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> Cost,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, Cost)>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, Cost)>,
+{
+    let mut paths = Paths::new(start.clone());
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), 0, 0))]);
+    let mut nodes = vec![start];
+
+    while let Some(Reverse((_, cost, i))) = queue.pop() {
+        let node = nodes[i].clone();
+        if paths.cost(&node).is_some_and(|best| cost > best) {
+            continue;
+        }
+        if is_goal(&node) {
+            return Some((paths.path_to(&node)?, cost));
+        }
+        for (next, step) in neighbors(&node) {
+            if paths.relax(&next, &node, cost + step) {
+                let estimate = cost + step + heuristic(&next);
+                queue.push(Reverse((estimate, cost + step, nodes.len())));
+                nodes.push(next);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Grid, Pos};
+
+    // Two equally short ways around the wall
+    const MAZE: &str = "\
+...
+.#.
+...
+#..
+";
+
+    fn maze() -> Grid<bool> {
+        Grid::parse(MAZE, |c| Ok(c == '#')).unwrap()
+    }
+
+    fn open(grid: &Grid<bool>, pos: &Pos) -> Vec<Pos> {
+        grid.neighbors4(*pos).filter(|next| !grid[*next]).collect()
+    }
+
+    #[test]
+    fn unweighted() {
+        let grid = maze();
+        let paths = bfs((0, 0), |pos| open(&grid, pos));
+        assert_eq!(paths.cost(&(2, 2)), Some(4));
+        assert_eq!(paths.count_paths(&(2, 2)), 2);
+        assert_eq!(paths.on_any_path(&(2, 2)).len(), 8);
+        assert_eq!(paths.path_to(&(0, 2)).unwrap(), [(0, 0), (0, 1), (0, 2)]);
+        assert_eq!(paths.cost(&(0, 3)), None);
+
+        assert_eq!(
+            dfs((0, 0), |pos| open(&grid, pos))[..3],
+            [(0, 0), (1, 0), (2, 0)]
+        );
+        assert_eq!(reachable((0, 0), |pos| open(&grid, pos)).len(), 10);
+    }
+
+    #[test]
+    fn weighted() {
+        let grid = maze();
+        // stepping down is dearer than across
+        let neighbors = |pos: &Pos| {
+            open(&grid, pos)
+                .into_iter()
+                .map(|next| (next, if next.1 > pos.1 { 5 } else { 1 }))
+                .collect::<Vec<_>>()
+        };
+
+        let paths = dijkstra((0, 0), neighbors);
+        assert_eq!(paths.cost(&(2, 2)), Some(12));
+        assert_eq!(paths.count_paths(&(2, 2)), 2);
+
+        let goal = (1, 3);
+        let heuristic = |pos: &Pos| (pos.0.abs_diff(goal.0) + pos.1.abs_diff(goal.1)) as Cost;
+        let (path, cost) = astar((0, 0), neighbors, heuristic, |pos| *pos == goal).unwrap();
+        assert_eq!(cost, paths.cost(&goal).unwrap());
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&goal));
+        assert!(astar((0, 0), neighbors, |_| 0, |pos| *pos == (0, 3)).is_none());
+    }
+
+    #[test]
+    fn zero_cost_cycles() {
+        // free steps round a ring, back to the start included
+        let neighbors = |n: &u8| [((n + 1) % 3, 0)];
+        let paths = dijkstra(0, neighbors);
+        assert!(paths.predecessors(&0).is_empty());
+        assert_eq!(paths.path_to(&2).unwrap(), [0, 1, 2]);
+        assert_eq!(paths.count_paths(&2), 1);
+        assert_eq!(paths.on_any_path(&2).len(), 3);
+
+        let (path, cost) = astar(0, neighbors, |_| 0, |n| *n == 2).unwrap();
+        assert_eq!((path, cost), (vec![0, 1, 2], 0));
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{error::AocError, params::Param};
+use std::{any::Any, fmt, time::Duration};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    I32(i32),
+    I64(i64),
+    Usize(usize),
+}
+
+impl Answer {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Answer::I32(_) => "i32",
+            Answer::I64(_) => "i64",
+            Answer::Usize(_) => "usize",
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::I32(value) => write!(f, "{value}"),
+            Answer::I64(value) => write!(f, "{value}"),
+            Answer::Usize(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(value: i32) -> Self {
+        Answer::I32(value)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::I64(value)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Usize(value)
+    }
+}
+
+/// A single day's puzzle. The input is parsed once and shared by both parts.
+pub trait Solution {
+    const DAY: u8;
+    const TITLE: &'static str;
+    const HAS_PART_TWO: bool = true;
+    /// How long each part may run before it is stopped, unless overridden.
+    const TIMEOUT: Option<Duration> = None;
+    /// Parameters the parts read through `params::get`.
+    const PARAMS: &'static [Param] = &[];
+
+    type Input: 'static;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError>;
+
+    fn part_one(input: &Self::Input) -> Result<Answer, AocError>;
+
+    fn part_two(_input: &Self::Input) -> Result<Answer, AocError> {
+        Err(AocError::invalid(format!(
+            "day {} has no part two",
+            Self::DAY
+        )))
+    }
+}
+
+/// Object safe view of a `Solution`, so every day can live in one registry.
+pub trait DynSolution: Sync {
+    fn day(&self) -> u8;
+    fn title(&self) -> &'static str;
+    fn has_part_two(&self) -> bool;
+    fn timeout(&self) -> Option<Duration>;
+    fn params(&self) -> &'static [Param];
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, AocError>;
+    fn part_one(&self, input: &dyn Any) -> Result<Answer, AocError>;
+    fn part_two(&self, input: &dyn Any) -> Result<Answer, AocError>;
+}
+
+impl<S: Solution + Sync> DynSolution for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn has_part_two(&self) -> bool {
+        S::HAS_PART_TWO
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        S::TIMEOUT
+    }
+
+    fn params(&self) -> &'static [Param] {
+        S::PARAMS
+    }
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, AocError> {
+        Ok(Box::new(S::parse(input)?))
+    }
+
+    fn part_one(&self, input: &dyn Any) -> Result<Answer, AocError> {
+        S::part_one(input.downcast_ref().expect("Input parsed by another day"))
+    }
+
+    fn part_two(&self, input: &dyn Any) -> Result<Answer, AocError> {
+        S::part_two(input.downcast_ref().expect("Input parsed by another day"))
+    }
+}
+
+/// Declares every day's module and registers its solution, in day order.
+#[macro_export]
+macro_rules! solutions {
+    ($($module:ident::$solution:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        pub fn solutions() -> &'static [&'static dyn $crate::solution::DynSolution] {
+            &[$(&$module::$solution),*]
+        }
+    };
+}
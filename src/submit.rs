@@ -0,0 +1,290 @@
+use crate::{
+    answers::Answers,
+    cli::{Selection, SubmitArgs},
+    fetch,
+    runner::{self, Status},
+};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fmt,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    RateLimited { wait_secs: u64 },
+    AlreadySolved,
+    Unrecognised,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "correct"),
+            Outcome::TooHigh => write!(f, "too high"),
+            Outcome::TooLow => write!(f, "too low"),
+            Outcome::Wrong => write!(f, "wrong"),
+            Outcome::RateLimited { wait_secs } => {
+                write!(f, "rate limited, wait {wait_secs}s")
+            }
+            Outcome::AlreadySolved => write!(f, "already solved"),
+            Outcome::Unrecognised => write!(f, "not understood"),
+        }
+    }
+}
+
+/// One submitted answer, stored as a line of JSON.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Guess {
+    pub timestamp: u64,
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub outcome: Outcome,
+}
+
+/// Reads the verdict out of the page the server returns for a submission.
+pub fn parse_outcome(page: &str) -> Outcome {
+    if page.contains("That's the right answer") {
+        Outcome::Correct
+    } else if page.contains("That's not the right answer") {
+        if page.contains("too high") {
+            Outcome::TooHigh
+        } else if page.contains("too low") {
+            Outcome::TooLow
+        } else {
+            Outcome::Wrong
+        }
+    } else if page.contains("You gave an answer too recently") {
+        let re = Regex::new(r"you have (?:(\d+)m )?(\d+)s left to wait").unwrap();
+        let wait_secs = re.captures(page).map_or(60, |caps| {
+            let minutes: u64 = caps.get(1).map_or(0, |m| m.as_str().parse().unwrap_or(0));
+            let seconds: u64 = caps[2].parse().unwrap_or(0);
+            minutes * 60 + seconds
+        });
+        Outcome::RateLimited { wait_secs }
+    } else if page.contains("You don't seem to be solving the right level") {
+        Outcome::AlreadySolved
+    } else {
+        Outcome::Unrecognised
+    }
+}
+
+/// Why `answer` shouldn't be sent, judging by earlier guesses for the same
+/// part, or `None` if it is worth a try.
+pub fn refusal(guesses: &[Guess], day: u8, part: u8, answer: &str, now: u64) -> Option<String> {
+    let guesses: Vec<&Guess> = guesses
+        .iter()
+        .filter(|g| g.day == day && g.part == part)
+        .collect();
+
+    if let Some(correct) = guesses.iter().find(|g| g.outcome == Outcome::Correct) {
+        return Some(format!("already solved with {}", correct.answer));
+    }
+
+    if let Some(wait) = guesses.iter().rev().find_map(|g| match g.outcome {
+        Outcome::RateLimited { wait_secs } => Some(g.timestamp + wait_secs),
+        _ => None,
+    }) {
+        if wait > now {
+            return Some(format!("rate limited for another {}s", wait - now));
+        }
+    }
+
+    if let Some(guess) = guesses.iter().find(|g| g.answer == answer) {
+        if matches!(
+            guess.outcome,
+            Outcome::TooHigh | Outcome::TooLow | Outcome::Wrong
+        ) {
+            return Some(format!(
+                "{answer} was already guessed and is {}",
+                guess.outcome
+            ));
+        }
+    }
+
+    let value: i128 = answer.parse().ok()?;
+    let bound = |outcome: Outcome| {
+        guesses
+            .iter()
+            .filter(move |g| g.outcome == outcome)
+            .filter_map(|g| g.answer.parse::<i128>().ok())
+    };
+    if let Some(high) = bound(Outcome::TooHigh).min().filter(|high| value >= *high) {
+        return Some(format!("{answer} is not below {high}, which was too high"));
+    }
+    if let Some(low) = bound(Outcome::TooLow).max().filter(|low| value <= *low) {
+        return Some(format!("{answer} is not above {low}, which was too low"));
+    }
+
+    None
+}
+
+fn load(path: &Path) -> Result<Vec<Guess>, Box<dyn Error>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path)?;
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| format!("{}:{}: {e}", path.display(), i + 1).into())
+        })
+        .collect()
+}
+
+fn append(path: &Path, guess: &Guess) -> Result<(), Box<dyn Error>> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(guess)?)?;
+    Ok(())
+}
+
+pub fn submit(args: &SubmitArgs) -> Result<(), Box<dyn Error>> {
+    let plan = runner::plan(&Selection {
+        days: Some(args.day..=args.day),
+        all: false,
+        part: Some(args.part),
+        input: None,
+        timeout: None,
+    })?;
+    runner::check_inputs(&plan)?;
+
+    let record = runner::execute_all(&plan, false).remove(0);
+    let label = runner::part_label(args.day, args.part);
+    let (Status::Ok, Some(answer)) = (record.status, record.answer) else {
+        let message = record.message.unwrap_or_default();
+        return Err(format!("{label} did not produce an answer: {message}").into());
+    };
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let guesses = load(&args.log)?;
+    if let Some(reason) = refusal(&guesses, args.day, args.part, &answer, now) {
+        return Err(format!("not submitting {answer} for {label}: {reason}").into());
+    }
+
+    let mut client = fetch::connect(&args.config, args.base_url.as_deref())?;
+    let outcome = parse_outcome(&client.submit(args.day, args.part, &answer)?);
+    append(
+        &args.log,
+        &Guess {
+            timestamp: now,
+            day: args.day,
+            part: args.part,
+            answer: answer.clone(),
+            outcome: outcome.clone(),
+        },
+    )?;
+
+    println!("\t{label} {answer}: {outcome}");
+    if outcome != Outcome::Correct {
+        return Err(format!("{answer} was not accepted").into());
+    }
+
+    let mut answers = Answers::load(&args.answers)?;
+    if answers.get(args.day, args.part).is_none() {
+        answers.set(args.day, args.part, answer);
+        answers.save(&args.answers)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::{mock::serve, Client};
+    use std::time::Duration;
+
+    fn guess(answer: &str, outcome: Outcome) -> Guess {
+        Guess {
+            timestamp: 1000,
+            day: 7,
+            part: 2,
+            answer: answer.to_string(),
+            outcome,
+        }
+    }
+
+    #[test]
+    fn outcomes() {
+        assert_eq!(
+            parse_outcome("<p>That's the right answer! You are one gold star closer"),
+            Outcome::Correct
+        );
+        assert_eq!(
+            parse_outcome("<p>That's not the right answer; your answer is too high."),
+            Outcome::TooHigh
+        );
+        assert_eq!(
+            parse_outcome("<p>That's not the right answer; your answer is too low."),
+            Outcome::TooLow
+        );
+        assert_eq!(
+            parse_outcome("<p>That's not the right answer. If you're stuck"),
+            Outcome::Wrong
+        );
+        assert_eq!(
+            parse_outcome("<p>You gave an answer too recently; you have 1m 12s left to wait."),
+            Outcome::RateLimited { wait_secs: 72 }
+        );
+        assert_eq!(
+            parse_outcome("<p>You don't seem to be solving the right level."),
+            Outcome::AlreadySolved
+        );
+        assert_eq!(parse_outcome("<html></html>"), Outcome::Unrecognised);
+    }
+
+    #[test]
+    fn refuses_known_bad_answers() {
+        let guesses = [
+            guess("500", Outcome::TooHigh),
+            guess("100", Outcome::TooLow),
+            guess("250", Outcome::Wrong),
+            guess("300", Outcome::RateLimited { wait_secs: 60 }),
+        ];
+
+        assert!(refusal(&guesses, 7, 2, "250", 2000).is_some());
+        assert!(refusal(&guesses, 7, 2, "500", 2000).is_some());
+        assert!(refusal(&guesses, 7, 2, "600", 2000).is_some());
+        assert!(refusal(&guesses, 7, 2, "99", 2000).is_some());
+        assert_eq!(refusal(&guesses, 7, 2, "300", 2000), None);
+        assert_eq!(refusal(&guesses, 7, 1, "600", 2000), None);
+        assert_eq!(
+            refusal(&guesses, 7, 2, "300", 1030),
+            Some("rate limited for another 30s".to_string())
+        );
+
+        let solved = [guess("300", Outcome::Correct)];
+        assert_eq!(
+            refusal(&solved, 7, 2, "301", 2000),
+            Some("already solved with 300".to_string())
+        );
+    }
+
+    #[test]
+    fn submits_form() {
+        let (url, requests) = serve(vec![(200, "<p>That's the right answer!</p>")]);
+        let mut client = Client::new(&url, "abc123", Duration::ZERO);
+
+        let page = client.submit(7, 2, "11387").unwrap();
+        assert_eq!(parse_outcome(&page), Outcome::Correct);
+
+        let request = requests.recv().unwrap();
+        assert_eq!(request.line, "POST /2024/day/7/answer HTTP/1.1");
+        assert_eq!(request.cookie, "session=abc123");
+        assert_eq!(request.body, "level=2&answer=11387");
+    }
+}
@@ -0,0 +1,64 @@
+use std::io::{self, IsTerminal};
+use tracing::Subscriber;
+use tracing_subscriber::EnvFilter;
+
+// Days log under their module path, e.g. `aoc_2024::day09`, so `day9`
+// and `9` name it too
+fn directive(spec: &str) -> String {
+    let crate_name = env!("CARGO_CRATE_NAME");
+    let Some((target, level)) = spec.split_once('=') else {
+        return format!("{crate_name}={}", spec.trim());
+    };
+    let target = target.trim();
+    let target = match target.strip_prefix("day").unwrap_or(target).parse::<u8>() {
+        Ok(day) => format!("day{day:02}"),
+        Err(_) => target.to_string(),
+    };
+    format!("{crate_name}::{target}={}", level.trim())
+}
+
+/// A filter from specs like `day09=debug`, or a bare level for every day.
+pub fn filter(specs: &[String]) -> Result<EnvFilter, String> {
+    specs
+        .iter()
+        .flat_map(|spec| spec.split(','))
+        .filter(|spec| !spec.trim().is_empty())
+        .try_fold(EnvFilter::new("off"), |filter, spec| {
+            let directive = directive(spec)
+                .parse()
+                .map_err(|e| format!("bad trace spec `{spec}`: {e}"))?;
+            Ok(filter.add_directive(directive))
+        })
+}
+
+/// Logs trace events to stderr, leaving answers alone on stdout.
+pub fn subscriber(specs: &[String]) -> Result<impl Subscriber + Send + Sync, String> {
+    Ok(tracing_subscriber::fmt()
+        .with_env_filter(filter(specs)?)
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal())
+        .without_time()
+        .finish())
+}
+
+pub fn init(specs: &[String]) -> Result<(), String> {
+    tracing::subscriber::set_global_default(subscriber(specs)?).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directives() {
+        assert_eq!(directive("day09=debug"), "aoc_2024::day09=debug");
+        assert_eq!(directive("day9=debug"), "aoc_2024::day09=debug");
+        assert_eq!(directive("trace"), "aoc_2024=trace");
+
+        let specs = ["9=debug,day12=trace".to_string()];
+        let directives = filter(&specs).unwrap().to_string();
+        assert!(directives.contains("aoc_2024::day09=debug"));
+        assert!(directives.contains("aoc_2024::day12=trace"));
+        assert!(filter(&["day09=loud".to_string()]).is_err());
+    }
+}
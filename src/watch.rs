@@ -0,0 +1,254 @@
+use crate::{
+    cli::WatchArgs,
+    runner::{part_label, Status},
+};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use serde::Deserialize;
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+    sync::mpsc::{self, Receiver},
+    time::Duration,
+};
+
+/// The parts of a runner record that are compared between runs.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PartRun {
+    pub day: u8,
+    pub part: u8,
+    pub status: Status,
+    pub answer: Option<String>,
+    pub duration_ms: Option<f64>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Examples {
+    pub passed: usize,
+    pub failed: Vec<String>,
+}
+
+// Anything else in the directory, such as editor swap files, is ignored
+fn relevant(event: &Event) -> bool {
+    !matches!(event.kind, EventKind::Access(_))
+        && event.paths.iter().any(|path| {
+            matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("rs" | "txt" | "toml")
+            )
+        })
+}
+
+fn wait_for_change(events: &Receiver<notify::Result<Event>>) -> Result<(), Box<dyn Error>> {
+    loop {
+        if relevant(&events.recv()??) {
+            break;
+        }
+    }
+    // Editors often save a file in several steps, so let those settle
+    while events.recv_timeout(Duration::from_millis(200)).is_ok() {}
+    Ok(())
+}
+
+fn cargo(args: &WatchArgs, command: &str) -> Command {
+    let mut cargo = Command::new("cargo");
+    cargo.arg(command);
+    if !args.debug {
+        cargo.arg("--release");
+    }
+    cargo
+}
+
+// Cargo's own output is only worth showing when the build broke
+fn build_failed(output: &Output) -> bool {
+    if output.status.success() || !output.stdout.is_empty() {
+        return false;
+    }
+    eprintln!("{}", String::from_utf8_lossy(&output.stderr));
+    true
+}
+
+fn run_parts(args: &WatchArgs) -> Result<Option<Vec<PartRun>>, Box<dyn Error>> {
+    let output = cargo(args, "run")
+        .args([
+            "--quiet",
+            "--",
+            "run",
+            &args.day.to_string(),
+            "--format",
+            "json",
+            // re-runs, often of debug builds, would swamp the timing history
+            "--no-history",
+        ])
+        .output()?;
+    if build_failed(&output) {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_slice(&output.stdout)?))
+}
+
+// Reads libtest's `test <name> ... ok` lines
+fn parse_examples(stdout: &str) -> Examples {
+    let mut examples = Examples::default();
+    for line in stdout.lines() {
+        let Some((name, result)) = line
+            .strip_prefix("test ")
+            .and_then(|line| line.split_once(" ... "))
+        else {
+            continue;
+        };
+        match result {
+            "ok" => examples.passed += 1,
+            "FAILED" => examples.failed.push(name.to_string()),
+            _ => {}
+        }
+    }
+    examples
+}
+
+fn run_examples(args: &WatchArgs) -> Result<Option<Examples>, Box<dyn Error>> {
+    let output = cargo(args, "test")
+        .args(["--test", "examples", "--", &format!("day{:02}_", args.day)])
+        .output()?;
+    if build_failed(&output) {
+        return Ok(None);
+    }
+    Ok(Some(parse_examples(&String::from_utf8_lossy(
+        &output.stdout,
+    ))))
+}
+
+/// One line per part, showing what changed since the previous run.
+pub fn report(current: &[PartRun], previous: &[PartRun]) -> Vec<String> {
+    current
+        .iter()
+        .map(|run| {
+            let label = part_label(run.day, run.part);
+            let message = run.message.as_deref().unwrap_or_default();
+            match run.status {
+                Status::Ok => {}
+                Status::Failed => return format!("{label} failed: {message}"),
+                Status::Missing => return format!("{label} missing input: {message}"),
+                Status::TimedOut => return format!("{label} {message}"),
+            }
+
+            let before = previous
+                .iter()
+                .find(|p| p.day == run.day && p.part == run.part && p.status == Status::Ok);
+            let was_duration = before
+                .and_then(|p| p.duration_ms)
+                .map(|ms| format!(" (was {ms:.1}ms)"))
+                .unwrap_or_default();
+            let answer = run.answer.as_deref().unwrap_or_default();
+            let was_answer = before
+                .and_then(|p| p.answer.as_deref())
+                .filter(|was| *was != answer)
+                .map(|was| format!(" (was {was})"))
+                .unwrap_or_default();
+
+            format!(
+                "{label} in {:>6.1}ms{was_duration} -> {answer}{was_answer}",
+                run.duration_ms.unwrap_or_default()
+            )
+        })
+        .collect()
+}
+
+pub fn watch(args: &WatchArgs) -> Result<(), Box<dyn Error>> {
+    let dir = PathBuf::from(format!("src/day{:02}", args.day));
+    if !dir.is_dir() {
+        return Err(format!("{} does not exist", dir.display()).into());
+    }
+
+    let (tx, events) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(Path::new(&dir), RecursiveMode::Recursive)?;
+
+    let mut previous = Vec::new();
+    loop {
+        println!("\n\tRunning day {}", args.day);
+        match run_parts(args)? {
+            Some(current) => {
+                for line in report(&current, &previous) {
+                    println!("\t{line}");
+                }
+                previous = current;
+            }
+            None => println!("\tBuild failed"),
+        }
+        if let Some(examples) = run_examples(args)? {
+            println!("\tExamples: {} passed", examples.passed);
+            for name in examples.failed {
+                println!("\tExample {name} FAILED");
+            }
+        }
+
+        println!("\tWatching {} for changes", dir.display());
+        wait_for_change(&events)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, ModifyKind};
+
+    fn run(part: u8, answer: &str, duration_ms: f64) -> PartRun {
+        PartRun {
+            day: 7,
+            part,
+            status: Status::Ok,
+            answer: Some(answer.to_string()),
+            duration_ms: Some(duration_ms),
+            message: None,
+        }
+    }
+
+    #[test]
+    fn compares_runs() {
+        let previous = [run(1, "3749", 2.0), run(2, "11386", 80.0)];
+        let mut failed = run(2, "", 0.0);
+        failed.status = Status::TimedOut;
+        failed.message = Some("timed out after 1s".to_string());
+
+        assert_eq!(
+            report(&[run(1, "3749", 1.5), failed], &previous),
+            [
+                "day07::part_one in    1.5ms (was 2.0ms) -> 3749",
+                "day07::part_two timed out after 1s",
+            ]
+        );
+        assert_eq!(
+            report(&[run(2, "11387", 75.25)], &previous),
+            ["day07::part_two in   75.2ms (was 80.0ms) -> 11387 (was 11386)"]
+        );
+        assert_eq!(
+            report(&[run(1, "3749", 1.0)], &[]),
+            ["day07::part_one in    1.0ms -> 3749"]
+        );
+    }
+
+    #[test]
+    fn reads_test_results() {
+        let examples = parse_examples(
+            "running 2 tests\ntest day07_test_part_one ... ok\ntest day07_test_part_two ... FAILED\n",
+        );
+        assert_eq!(examples.passed, 1);
+        assert_eq!(examples.failed, ["day07_test_part_two"]);
+    }
+
+    #[test]
+    fn filters_events() {
+        let event = |kind, path: &str| Event::new(kind).add_path(PathBuf::from(path));
+        let modify = EventKind::Modify(ModifyKind::Any);
+
+        assert!(relevant(&event(modify, "src/day07/mod.rs")));
+        assert!(relevant(&event(modify, "src/day07/test.txt")));
+        assert!(!relevant(&event(modify, "src/day07/.mod.rs.swp")));
+        assert!(!relevant(&event(
+            EventKind::Access(AccessKind::Any),
+            "src/day07/mod.rs"
+        )));
+    }
+}
@@ -0,0 +1,63 @@
+use aoc_2024::{day04, day07, day12, day13, runner, solutions, Answer};
+
+#[test]
+fn registry_is_in_day_order() {
+    let days: Vec<u8> = solutions().iter().map(|s| s.day()).collect();
+    assert!(days.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(days[0], 1);
+}
+
+#[test]
+fn solves_from_text() {
+    let solution = runner::find_solution(7).unwrap();
+    let input = solution
+        .parse(include_str!("../src/day07/test.txt"))
+        .unwrap();
+    assert_eq!(
+        solution.part_two(input.as_ref()).unwrap(),
+        Answer::I64(11387)
+    );
+}
+
+#[test]
+fn missing_part_two_is_an_error() {
+    let solution = runner::find_solution(15).unwrap();
+    assert!(!solution.has_part_two());
+    let input = solution
+        .parse(include_str!("../src/day15/smalltest.txt"))
+        .unwrap();
+    let err = solution.part_two(input.as_ref()).unwrap_err();
+    assert_eq!(err.to_string(), "invalid puzzle: day 15 has no part two");
+}
+
+#[test]
+fn day_modules() {
+    let equations = day07::parse("190: 10 19\n83: 17 5\n").unwrap();
+    assert_eq!(day07::part_one(&equations).unwrap(), 190);
+
+    let finder = day04::parse(include_str!("../src/day04/test.txt")).unwrap();
+    assert_eq!(finder.find_word("XMAS").len(), 18);
+
+    let garden = day12::parse(include_str!("../src/day12/example.txt")).unwrap();
+    assert_eq!(garden.price_of(&'A'), 40);
+    assert_eq!(garden.discounted_price_of(&'A'), 16);
+
+    let machines = day13::parse(include_str!("../src/day13/test.txt")).unwrap();
+    assert_eq!(machines[0].solution().unwrap(), Some(280));
+    assert_eq!(machines[1].solution().unwrap(), None);
+    assert_eq!(
+        machines[1]
+            .with_prize_offset(10000000000000)
+            .solution()
+            .unwrap(),
+        Some(459236326669)
+    );
+}
+
+#[test]
+fn malformed_input_is_an_error() {
+    let err = day07::parse("190 10 19\n").unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("parse error at line 1, column 1"));
+}
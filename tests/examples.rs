@@ -0,0 +1,14 @@
+// Generated from each day's `examples.toml` by build.rs
+use aoc_2024::runner::find_solution;
+
+fn check(day: u8, part: u8, input: &str, expected: &str) {
+    let solution = find_solution(day).expect("day is registered");
+    let input = solution.parse(input).unwrap();
+    let answer = match part {
+        1 => solution.part_one(input.as_ref()),
+        _ => solution.part_two(input.as_ref()),
+    };
+    assert_eq!(answer.unwrap().to_string(), expected);
+}
+
+include!(concat!(env!("OUT_DIR"), "/examples.rs"));